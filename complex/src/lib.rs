@@ -1,9 +1,13 @@
 #![allow(dead_code, unused_imports, unused_variables, 
          unused_mut, non_snake_case, deprecated)]
 
-use std::ops::{Add, Sub, Mul, Div};
+use std::ops::{Add, Sub, Mul, Div, Neg};
 use std::fmt;
+use vectors::{Scalar, Vector};
 
+pub use errors::LinalgError;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Complex {
     real: f64,
     imaginary: f64,
@@ -17,6 +21,14 @@ impl Complex {
         }
     }
 
+    pub fn real(&self) -> f64 {
+        self.real
+    }
+
+    pub fn imaginary(&self) -> f64 {
+        self.imaginary
+    }
+
     pub fn conjugate(&self) -> Self {
         Self::new(self.real, -self.imaginary)
     }
@@ -49,6 +61,13 @@ impl Complex {
         Self::new(real, imag)
     }
 
+    pub fn try_div(&self, other: &Complex) -> Result<Self, LinalgError> {
+        if other.real == 0.0 && other.imaginary == 0.0 {
+            return Err(LinalgError::DivisionByZero);
+        }
+
+        Ok(self / other)
+    }
 }
 
 impl Add<&Complex> for &Complex {
@@ -104,6 +123,79 @@ impl Div<&Complex> for &Complex {
     }
 }
 
+impl Add for Complex {
+    type Output = Complex;
+
+    fn add(self, other: Complex) -> Self::Output {
+        Add::add(&self, &other)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+
+    fn sub(self, other: Complex) -> Self::Output {
+        Sub::sub(&self, &other)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+
+    fn mul(self, other: Complex) -> Self::Output {
+        Mul::mul(&self, &other)
+    }
+}
+
+impl Div for Complex {
+    type Output = Complex;
+
+    fn div(self, other: Complex) -> Self::Output {
+        Div::div(&self, &other)
+    }
+}
+
+impl Neg for Complex {
+    type Output = Complex;
+
+    fn neg(self) -> Self::Output {
+        Complex::new(-self.real, -self.imaginary)
+    }
+}
+
+impl Scalar for Complex {
+    fn zero() -> Self {
+        Complex::new(0.0, 0.0)
+    }
+
+    fn one() -> Self {
+        Complex::new(1.0, 0.0)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        Complex::new(value, 0.0)
+    }
+
+    fn modulus(&self) -> f64 {
+        self.norm()
+    }
+
+    fn conjugate(&self) -> Self {
+        Complex::conjugate(self)
+    }
+
+    fn epsilon() -> f64 {
+        f64::EPSILON
+    }
+}
+
+impl Mul<&Vector<Complex>> for Complex {
+    type Output = Vector<Complex>;
+
+    fn mul(self, other: &Vector<Complex>) -> Self::Output {
+        other * self
+    }
+}
 
 impl fmt::Display for Complex {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
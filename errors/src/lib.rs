@@ -0,0 +1,79 @@
+use std::fmt;
+use std::error::Error;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinalgError {
+    // (rows, columns) expected by the operation and the ones received.
+    // vectors report their dimension as (1, size)
+    DimensionMismatch {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+    IndexOutOfBounds {
+        index: (usize, usize),
+        dimension: (usize, usize),
+    },
+    NotSquare {
+        rows: usize,
+        columns: usize,
+    },
+    NotSymmetric,
+    NotPositiveDefinite {
+        pivot: usize,
+    },
+    Singular,
+    NoSolution,
+    NoConvergence {
+        iterations: usize,
+    },
+    DivisionByZero,
+    Empty,
+    // an argument outside the values the method accepts
+    InvalidParameter {
+        parameter: &'static str,
+        requirement: &'static str,
+    },
+    // the operator doesn't provide what the method needs,
+    // e.g. its diagonal or its rows
+    Unsupported {
+        operation: &'static str,
+    },
+}
+
+impl fmt::Display for LinalgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinalgError::DimensionMismatch { expected, found } => {
+                write!(f, "dimension mismatch: expected {}x{}, found {}x{}",
+                       expected.0, expected.1, found.0, found.1)
+            }
+            LinalgError::IndexOutOfBounds { index, dimension } => {
+                write!(f, "index ({}, {}) is out of bounds for dimension {}x{}",
+                       index.0, index.1, dimension.0, dimension.1)
+            }
+            LinalgError::NotSquare { rows, columns } => {
+                write!(f, "matrix is not square ({}x{})", rows, columns)
+            }
+            LinalgError::NotSymmetric => write!(f, "matrix is not symmetric"),
+            LinalgError::NotPositiveDefinite { pivot } => {
+                write!(f, "matrix is not positive definite (pivot {} is not positive)",
+                       pivot)
+            }
+            LinalgError::Singular => write!(f, "matrix is singular"),
+            LinalgError::NoSolution => write!(f, "there is no solution"),
+            LinalgError::NoConvergence { iterations } => {
+                write!(f, "no convergence after {} iterations", iterations)
+            }
+            LinalgError::DivisionByZero => write!(f, "division by zero"),
+            LinalgError::Empty => write!(f, "tried to create an object with no elements"),
+            LinalgError::InvalidParameter { parameter, requirement } => {
+                write!(f, "{} must be {}", parameter, requirement)
+            }
+            LinalgError::Unsupported { operation } => {
+                write!(f, "the operator doesn't support {}", operation)
+            }
+        }
+    }
+}
+
+impl Error for LinalgError {}
@@ -0,0 +1,60 @@
+// cargo bench -p matrices --bench gemm
+//
+// compares the blocked parallel kernel behind `&A * &B` and `Matrix::gemm`
+// with the previous operator, which computed A times each column of B
+
+use std::time::{Duration, Instant};
+
+use matrices::Matrix;
+use vectors::Vector;
+
+fn test_matrix(rows: usize, columns: usize, seed: f64) -> Matrix {
+    let row_vectors = (0..rows)
+                      .map(|i| {
+                          let row = (0..columns)
+                                    .map(|j| ((i * columns + j) as f64 + seed).sin())
+                                    .collect::<Vec<f64>>();
+
+                          Vector::create_with_vec(row)
+                      })
+                      .collect::<Vec<Vector>>();
+
+    Matrix::create_with_vectors(&row_vectors)
+}
+
+fn column_product(a: &Matrix, b: &Matrix) -> Matrix {
+    let (_, n) = b.dimension();
+    let columns = (1..=n)
+                  .map(|j| a * &b.get_column(j).unwrap())
+                  .collect::<Vec<Vector>>();
+
+    Matrix::create_with_vectors(&columns).transpose()
+}
+
+// best of a few runs, the first one warms up caches and the thread pool
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    f();
+
+    (0..3).map(|_| {
+        let start = Instant::now();
+        f();
+        start.elapsed()
+    }).min().unwrap()
+}
+
+fn main() {
+    println!("{:>6} {:>14} {:>14} {:>14} {:>9}", "n", "columns", "operator", "gemm", "speedup");
+
+    for &n in [64, 128, 256, 512].iter() {
+        let a = test_matrix(n, n, 0.0);
+        let b = test_matrix(n, n, 1.0);
+        let mut c = test_matrix(n, n, 2.0);
+
+        let old = time(|| { column_product(&a, &b); });
+        let new = time(|| { let _ = &a * &b; });
+        let in_place = time(|| Matrix::gemm(1.5, &a, &b, 0.5, &mut c));
+
+        println!("{:>6} {:>14.3?} {:>14.3?} {:>14.3?} {:>8.1}x",
+                 n, old, new, in_place, old.as_secs_f64() / new.as_secs_f64());
+    }
+}
@@ -0,0 +1,543 @@
+use crate::*;
+use std::cmp::Ordering;
+
+// |a_ij - a_ji| <= tolerance |A|_F for every pair, as in symmetric_eigen.
+// the factorizations below then only read the lower triangle
+fn check_symmetric(matrix: &Matrix, tolerance: f64) -> Result<(), LinalgError> {
+    matrix.check_square()?;
+
+    let n = matrix.dimension().0;
+    let a = &matrix.data;
+    let norm = a.iter().map(|x| x * x).sum::<f64>().sqrt();
+
+    for i in 0..n {
+        for j in (i + 1)..n {
+            if (a[i * n + j] - a[j * n + i]).abs() > tolerance * norm {
+                return Err(LinalgError::NotSymmetric);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// A = L L^T with L lower triangular and a positive diagonal
+#[derive(Debug, Clone, PartialEq)]
+pub struct CholeskyFactor {
+    l: Matrix,
+}
+
+impl CholeskyFactor {
+    fn new(matrix: &Matrix, tolerance: f64) -> Result<Self, LinalgError> {
+        // fails at the first pivot that is not positive (or is nan), which
+        // happens exactly when A is not positive definite
+        check_symmetric(matrix, tolerance)?;
+
+        let n = matrix.dimension().0;
+        let a = &matrix.data;
+        let mut l = vec![0.0; n * n];
+
+        for j in 0..n {
+            let d = a[j * n + j] - (0..j).map(|k| l[j * n + k] * l[j * n + k]).sum::<f64>();
+
+            if d <= 0.0 || d.is_nan() {
+                return Err(LinalgError::NotPositiveDefinite { pivot: j + 1 });
+            }
+
+            let ljj = d.sqrt();
+            l[j * n + j] = ljj;
+
+            for i in (j + 1)..n {
+                let sum = (0..j).map(|k| l[i * n + k] * l[j * n + k]).sum::<f64>();
+                l[i * n + j] = (a[i * n + j] - sum) / ljj;
+            }
+        }
+
+        Ok(Self { l: Matrix::from_data(n, n, l) })
+    }
+
+    pub fn l(&self) -> &Matrix {
+        &self.l
+    }
+
+    pub fn solve(&self, b: &Vector) -> Vector {
+        self.try_solve(b).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_solve(&self, b: &Vector) -> Result<Vector, LinalgError> {
+        // L y = b and L^T x = y
+        let n = self.l.dimension().0;
+
+        if b.size() != n {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (1, n),
+                found: b.dimension(),
+            });
+        }
+
+        let l = &self.l.data;
+        let mut x = b.components.clone();
+
+        for i in 0..n {
+            let sum = (0..i).map(|k| l[i * n + k] * x[k]).sum::<f64>();
+            x[i] = (x[i] - sum) / l[i * n + i];
+        }
+
+        for i in (0..n).rev() {
+            let sum = ((i + 1)..n).map(|k| l[k * n + i] * x[k]).sum::<f64>();
+            x[i] = (x[i] - sum) / l[i * n + i];
+        }
+
+        Ok(Vector::create_with_vec(x))
+    }
+
+    pub fn solve_matrix(&self, b: &Matrix) -> Matrix {
+        self.try_solve_matrix(b).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_solve_matrix(&self, b: &Matrix) -> Result<Matrix, LinalgError> {
+        let (rows, k) = b.dimension();
+        let n = self.l.dimension().0;
+
+        if rows != n {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (n, k),
+                found: (rows, k),
+            });
+        }
+
+        let mut x = Matrix::zero(n, k);
+
+        for j in 1..=k {
+            let column = self.try_solve(&b.get_column(j).unwrap())?;
+            x.change_column(j, column);
+        }
+
+        Ok(x)
+    }
+
+    pub fn inverse(&self) -> Matrix {
+        let n = self.l.dimension().0;
+
+        self.solve_matrix(&Matrix::identity(n))
+    }
+
+    pub fn determinant(&self) -> f64 {
+        let n = self.l.dimension().0;
+
+        (0..n).map(|i| self.l.data[i * n + i].powi(2)).product()
+    }
+
+    // ln det A, which doesn't overflow for large matrices
+    pub fn log_determinant(&self) -> f64 {
+        let n = self.l.dimension().0;
+
+        (0..n).map(|i| 2.0 * self.l.data[i * n + i].ln()).sum()
+    }
+
+    // the factor of A + x x^T
+    pub fn update(&mut self, x: &Vector) {
+        self.try_update(x).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_update(&mut self, x: &Vector) -> Result<(), LinalgError> {
+        Decomposer::new().try_cholesky_update(&mut self.l, x)
+    }
+
+    // the factor of A - x x^T, unchanged when that is not positive definite
+    pub fn downdate(&mut self, x: &Vector) {
+        self.try_downdate(x).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_downdate(&mut self, x: &Vector) -> Result<(), LinalgError> {
+        Decomposer::new().try_cholesky_downdate(&mut self.l, x)
+    }
+}
+
+// bunch-kaufman factorization P A P^T = L D L^T of a symmetric, possibly
+// indefinite matrix. L is unit lower triangular and D block diagonal with
+// 1x1 and 2x2 blocks, so no pivot needs to be positive
+#[derive(Debug, Clone, PartialEq)]
+pub struct LdlFactor {
+    l: Matrix,
+    d: Matrix,
+    permutation: Vec<usize>,
+    // first index (0 based) and size of each diagonal block of D
+    blocks: Vec<(usize, usize)>,
+}
+
+impl LdlFactor {
+    fn new(matrix: &Matrix, tolerance: f64) -> Result<Self, LinalgError> {
+        // at step k the pivot is chosen among a_kk, a_rr and the 2x2 block
+        // of k and r, where r holds the largest entry below a_kk. the bound
+        // alpha = (1 + sqrt(17)) / 8 keeps the growth of the entries as
+        // small as complete pivoting would
+        check_symmetric(matrix, tolerance)?;
+
+        let alpha = (1.0 + 17f64.sqrt()) / 8.0;
+        let n = matrix.dimension().0;
+        let mut a = matrix.data.clone();
+
+        for i in 0..n {
+            for j in (i + 1)..n {
+                a[i * n + j] = a[j * n + i];
+            }
+        }
+
+        let mut l = vec![0.0; n * n];
+        let mut d = vec![0.0; n * n];
+        let mut permutation = (1..=n).collect::<Vec<usize>>();
+        let mut blocks = vec![];
+
+        // symmetric swap of i and j >= k, in the trailing part of A, in the
+        // columns of L already computed and in the permutation
+        let swap = |a: &mut Vec<f64>, l: &mut Vec<f64>, permutation: &mut Vec<usize>, k: usize, i: usize, j: usize| {
+            if i == j {
+                return;
+            }
+
+            for c in k..n {
+                a.swap(i * n + c, j * n + c);
+            }
+
+            for r in k..n {
+                a.swap(r * n + i, r * n + j);
+            }
+
+            for c in 0..k {
+                l.swap(i * n + c, j * n + c);
+            }
+
+            permutation.swap(i, j);
+        };
+
+        let mut k = 0;
+
+        while k < n {
+            let (r, lambda) = ((k + 1)..n)
+                              .map(|i| (i, a[i * n + k].abs()))
+                              .fold((k, 0.0), |best, x| if x.1 > best.1 { x } else { best });
+            let akk = a[k * n + k].abs();
+
+            let size = if lambda == 0.0 || akk >= alpha * lambda {
+                1
+            } else {
+                let sigma = (k..n).filter(|&i| i != r).map(|i| a[i * n + r].abs()).fold(0.0, f64::max);
+
+                if akk * sigma >= alpha * lambda * lambda {
+                    1
+                } else if a[r * n + r].abs() >= alpha * sigma {
+                    swap(&mut a, &mut l, &mut permutation, k, k, r);
+                    1
+                } else {
+                    swap(&mut a, &mut l, &mut permutation, k, k + 1, r);
+                    2
+                }
+            };
+
+            if size == 1 {
+                let dk = a[k * n + k];
+                d[k * n + k] = dk;
+                l[k * n + k] = 1.0;
+
+                // a zero pivot only happens with a zero column, which
+                // needs no elimination
+                if dk != 0.0 {
+                    for i in (k + 1)..n {
+                        l[i * n + k] = a[i * n + k] / dk;
+                    }
+
+                    for i in (k + 1)..n {
+                        for j in (k + 1)..n {
+                            a[i * n + j] -= l[i * n + k] * a[j * n + k];
+                        }
+                    }
+                }
+            } else {
+                let (e11, e21, e22) = (a[k * n + k], a[(k + 1) * n + k], a[(k + 1) * n + k + 1]);
+                let det = e11 * e22 - e21 * e21;
+
+                d[k * n + k] = e11;
+                d[k * n + k + 1] = e21;
+                d[(k + 1) * n + k] = e21;
+                d[(k + 1) * n + k + 1] = e22;
+                l[k * n + k] = 1.0;
+                l[(k + 1) * n + k + 1] = 1.0;
+
+                // [l_ik l_i,k+1] = [a_ik a_i,k+1] E^-1
+                for i in (k + 2)..n {
+                    let (x, y) = (a[i * n + k], a[i * n + k + 1]);
+                    l[i * n + k] = (x * e22 - y * e21) / det;
+                    l[i * n + k + 1] = (y * e11 - x * e21) / det;
+                }
+
+                for i in (k + 2)..n {
+                    for j in (k + 2)..n {
+                        a[i * n + j] -= l[i * n + k] * a[j * n + k] + l[i * n + k + 1] * a[j * n + k + 1];
+                    }
+                }
+            }
+
+            blocks.push((k, size));
+            k += size;
+        }
+
+        Ok(Self {
+            l: Matrix::from_data(n, n, l),
+            d: Matrix::from_data(n, n, d),
+            permutation,
+            blocks,
+        })
+    }
+
+    pub fn l(&self) -> &Matrix {
+        &self.l
+    }
+
+    pub fn d(&self) -> &Matrix {
+        &self.d
+    }
+
+    // row i of P A P^T is row permutation[i - 1] of A
+    pub fn permutation(&self) -> &[usize] {
+        &self.permutation
+    }
+
+    pub fn p(&self) -> Matrix {
+        let n = self.permutation.len();
+        let mut p = Matrix::zero(n, n);
+
+        for (i, &row) in self.permutation.iter().enumerate() {
+            p.data[i * n + row - 1] = 1.0;
+        }
+
+        p
+    }
+
+    // sizes of the diagonal blocks of D, in order
+    pub fn block_sizes(&self) -> Vec<usize> {
+        self.blocks.iter().map(|&(_, size)| size).collect()
+    }
+
+    // (positive, negative, zero) eigenvalues of A, the same as for D
+    pub fn inertia(&self) -> (usize, usize, usize) {
+        let n = self.permutation.len();
+        let d = &self.d.data;
+        let mut inertia = (0, 0, 0);
+
+        for &(k, size) in self.blocks.iter() {
+            let eigenvalues = if size == 1 {
+                vec![d[k * n + k]]
+            } else {
+                let half_trace = (d[k * n + k] + d[(k + 1) * n + k + 1]) / 2.0;
+                let det = d[k * n + k] * d[(k + 1) * n + k + 1] - d[(k + 1) * n + k].powi(2);
+                let root = (half_trace * half_trace - det).max(0.0).sqrt();
+
+                vec![half_trace + root, half_trace - root]
+            };
+
+            for lambda in eigenvalues {
+                match lambda.partial_cmp(&0.0) {
+                    Some(Ordering::Greater) => inertia.0 += 1,
+                    Some(Ordering::Less) => inertia.1 += 1,
+                    _ => inertia.2 += 1,
+                }
+            }
+        }
+
+        inertia
+    }
+
+    pub fn determinant(&self) -> f64 {
+        let n = self.permutation.len();
+        let d = &self.d.data;
+
+        self.blocks.iter().map(|&(k, size)| {
+            if size == 1 {
+                d[k * n + k]
+            } else {
+                d[k * n + k] * d[(k + 1) * n + k + 1] - d[(k + 1) * n + k].powi(2)
+            }
+        }).product()
+    }
+
+    pub fn solve(&self, b: &Vector) -> Vector {
+        self.try_solve(b).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_solve(&self, b: &Vector) -> Result<Vector, LinalgError> {
+        // A x = b is L D L^T (P x) = P b
+        let n = self.permutation.len();
+
+        if b.size() != n {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (1, n),
+                found: b.dimension(),
+            });
+        }
+
+        let (l, d) = (&self.l.data, &self.d.data);
+        let mut y = self.permutation.iter().map(|&i| b.components[i - 1]).collect::<Vec<f64>>();
+
+        for i in 0..n {
+            let sum = (0..i).map(|k| l[i * n + k] * y[k]).sum::<f64>();
+            y[i] -= sum;
+        }
+
+        for &(k, size) in self.blocks.iter() {
+            if size == 1 {
+                if d[k * n + k] == 0.0 {
+                    return Err(LinalgError::Singular);
+                }
+
+                y[k] /= d[k * n + k];
+            } else {
+                let (e11, e21, e22) = (d[k * n + k], d[(k + 1) * n + k], d[(k + 1) * n + k + 1]);
+                let det = e11 * e22 - e21 * e21;
+
+                if det == 0.0 {
+                    return Err(LinalgError::Singular);
+                }
+
+                let (x, z) = (y[k], y[k + 1]);
+                y[k] = (x * e22 - z * e21) / det;
+                y[k + 1] = (z * e11 - x * e21) / det;
+            }
+        }
+
+        for i in (0..n).rev() {
+            let sum = ((i + 1)..n).map(|k| l[k * n + i] * y[k]).sum::<f64>();
+            y[i] -= sum;
+        }
+
+        let mut x = vec![0.0; n];
+
+        for (i, yi) in y.into_iter().enumerate() {
+            x[self.permutation[i] - 1] = yi;
+        }
+
+        Ok(Vector::create_with_vec(x))
+    }
+}
+
+impl Matrix {
+    // symmetric within the tolerance and with a cholesky factorization
+    pub fn is_positive_definite(&self, tolerance: f64) -> bool {
+        CholeskyFactor::new(self, tolerance).is_ok()
+    }
+}
+
+impl Decomposer {
+    pub fn cholesky_factor(&self, matrix: &Matrix, tolerance: f64) -> CholeskyFactor {
+        self.try_cholesky_factor(matrix, tolerance).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_cholesky_factor(&self, matrix: &Matrix, tolerance: f64) -> Result<CholeskyFactor, LinalgError> {
+        CholeskyFactor::new(matrix, tolerance)
+    }
+
+    pub fn ldlt(&self, matrix: &Matrix, tolerance: f64) -> LdlFactor {
+        self.try_ldlt(matrix, tolerance).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_ldlt(&self, matrix: &Matrix, tolerance: f64) -> Result<LdlFactor, LinalgError> {
+        LdlFactor::new(matrix, tolerance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn max_difference(a: &Matrix, b: &Matrix) -> f64 {
+        a.data.iter().zip(b.data.iter()).map(|(x, y)| (x - y).abs()).fold(0.0, f64::max)
+    }
+
+    fn check_ldlt(a: &Matrix) -> LdlFactor {
+        let n = a.dimension().0;
+        let factor = Decomposer::new().ldlt(a, 0.0);
+        let p = factor.p();
+        let pap = &(&p * a) * &p.transpose();
+        let ldl = &(factor.l() * factor.d()) * &factor.l().transpose();
+
+        assert!(max_difference(&pap, &ldl) < 1e-13);
+
+        let b = Vector::create_with_vec((1..=n).map(|i| i as f64).collect());
+        let x = factor.solve(&b);
+        let residual = &(a * &x) - &b;
+
+        assert!(residual.components.iter().all(|r| r.abs() < 1e-13));
+
+        factor
+    }
+
+    #[test]
+    fn ldlt_with_a_2x2_pivot() {
+        let factor = check_ldlt(&Matrix::new(&[&[0.0, 1.0], &[1.0, 0.0]]));
+
+        assert_eq!(factor.block_sizes(), vec![2]);
+        assert_eq!(factor.inertia(), (1, 1, 0));
+        assert_eq!(factor.determinant(), -1.0);
+    }
+
+    #[test]
+    fn ldlt_of_indefinite_matrices() {
+        let a = Matrix::new(&[&[1.0, 10.0, 0.0, 2.0],
+                              &[10.0, 1.0, 3.0, 0.0],
+                              &[0.0, 3.0, 0.0, 5.0],
+                              &[2.0, 0.0, 5.0, 4.0]]);
+        let factor = check_ldlt(&a);
+
+        assert_eq!(factor.block_sizes(), vec![2, 1, 1]);
+        assert_eq!(factor.inertia(), (2, 2, 0));
+        assert!((factor.determinant() - a.determinant()).abs() < 1e-10);
+
+        // 1x1 pivots after swapping in a larger diagonal entry
+        let a = Matrix::new(&[&[0.0, 1.0, 2.0, 0.0],
+                              &[1.0, 5.0, 0.0, 1.0],
+                              &[2.0, 0.0, -3.0, 1.0],
+                              &[0.0, 1.0, 1.0, 0.0]]);
+        let factor = check_ldlt(&a);
+
+        assert_eq!(factor.block_sizes(), vec![1, 1, 1, 1]);
+        assert_eq!(factor.permutation(), &[3, 2, 1, 4]);
+    }
+
+    #[test]
+    fn ldlt_of_singular_matrix() {
+        let a = Matrix::new(&[&[1.0, 2.0, 0.0], &[2.0, 4.0, 0.0], &[0.0, 0.0, 0.0]]);
+        let factor = Decomposer::new().ldlt(&a, 0.0);
+
+        assert_eq!(factor.inertia(), (1, 0, 2));
+        assert_eq!(factor.try_solve(&Vector::new(&[1.0, 2.0, 0.0])), Err(LinalgError::Singular));
+    }
+
+    #[test]
+    fn cholesky_reports_the_failing_pivot() {
+        let decomposer = Decomposer::new();
+        let a = Matrix::new(&[&[4.0, 2.0, 0.0], &[2.0, 1.0, 0.0], &[0.0, 0.0, 1.0]]);
+        let b = Matrix::new(&[&[1.0, 0.0, 0.0], &[0.0, 2.0, 3.0], &[0.0, 3.0, 1.0]]);
+
+        assert_eq!(decomposer.try_cholesky_factor(&a, 0.0), Err(LinalgError::NotPositiveDefinite { pivot: 2 }));
+        assert_eq!(decomposer.try_cholesky_factor(&b, 0.0), Err(LinalgError::NotPositiveDefinite { pivot: 3 }));
+        assert!(!b.is_positive_definite(0.0));
+    }
+
+    #[test]
+    fn cholesky_solve_and_symmetry_tolerance() {
+        let a = Matrix::new(&[&[4.0, 2.0, 1.0], &[2.0, 5.0, 3.0], &[1.0, 3.0, 6.0]]);
+        let factor = Decomposer::new().cholesky_factor(&a, 0.0);
+        let b = Vector::new(&[1.0, 2.0, 3.0]);
+        let residual = &(&a * &factor.solve(&b)) - &b;
+
+        assert!(residual.components.iter().all(|r| r.abs() < 1e-14));
+        assert!(max_difference(&(factor.l() * &factor.l().transpose()), &a) < 1e-14);
+        assert!((factor.determinant() - a.determinant()).abs() < 1e-12);
+
+        let mut perturbed = a.clone();
+        perturbed.change_element(1, 2, 2.0 + 1e-12);
+
+        assert_eq!(Decomposer::new().try_cholesky_factor(&perturbed, 0.0), Err(LinalgError::NotSymmetric));
+        assert!(perturbed.is_positive_definite(1e-10));
+    }
+}
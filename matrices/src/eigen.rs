@@ -0,0 +1,1000 @@
+use crate::*;
+use complex::Complex;
+
+// (eigenvalues, eigenvectors if they were computed and form a basis)
+type EigenPairs = (Vec<Complex>, Option<Vec<Vec<Complex>>>);
+
+pub struct SymmetricEigen {
+    eigenvalues: Vector,
+    eigenvectors: Option<Matrix>,
+}
+
+impl SymmetricEigen {
+    fn new
+    (
+        matrix: &Matrix,
+        error: f64,
+        max_iter: usize,
+        compute_vectors: bool,
+    ) -> Result<Self, LinalgError>
+    {
+        // cyclic jacobi: each sweep annihilates every off diagonal entry
+        // once, until the off diagonal norm is small relative to the matrix
+        matrix.check_square()?;
+
+        let n = matrix.dimension().0;
+        let norm = matrix.data.iter().map(|a| a * a).sum::<f64>().sqrt();
+
+        for i in 0..n {
+            for j in (i + 1)..n {
+                if (matrix.data[i * n + j] - matrix.data[j * n + i]).abs() > error * norm {
+                    return Err(LinalgError::NotSymmetric);
+                }
+            }
+        }
+
+        let mut a = matrix.clone();
+        let mut v = Matrix::identity(n);
+        let mut converged = false;
+
+        for _ in 0..max_iter {
+            if Self::off_diagonal_norm(&a) <= error * norm {
+                converged = true;
+                break;
+            }
+
+            for p in 0..n {
+                for q in (p + 1)..n {
+                    Self::rotate(&mut a, &mut v, p, q, compute_vectors);
+                }
+            }
+        }
+
+        if !converged && Self::off_diagonal_norm(&a) > error * norm {
+            return Err(LinalgError::NoConvergence { iterations: max_iter });
+        }
+
+        let mut order = (0..n).collect::<Vec<usize>>();
+        order.sort_by(|&i, &j| a.data[i * n + i].total_cmp(&a.data[j * n + j]));
+
+        let eigenvalues = order.iter()
+                          .map(|&i| a.data[i * n + i])
+                          .collect::<Vec<f64>>();
+
+        let eigenvectors = if compute_vectors {
+            let mut sorted = Matrix::zero(n, n);
+
+            for (j, &k) in order.iter().enumerate() {
+                for i in 0..n {
+                    sorted.data[i * n + j] = v.data[i * n + k];
+                }
+            }
+
+            Some(sorted)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            eigenvalues: Vector::create_with_vec(eigenvalues),
+            eigenvectors,
+        })
+    }
+
+    fn off_diagonal_norm(a: &Matrix) -> f64 {
+        let n = a.dimension().0;
+        let mut sum = 0.0;
+
+        for i in 0..n {
+            for j in 0..n {
+                if i != j {
+                    sum += a.data[i * n + j] * a.data[i * n + j];
+                }
+            }
+        }
+
+        sum.sqrt()
+    }
+
+    fn rotate(a: &mut Matrix, v: &mut Matrix, p: usize, q: usize, compute_vectors: bool) {
+        // A = J^T A J, with J chosen so that the new a_pq is zero
+        let n = a.dimension().0;
+        let apq = a.data[p * n + q];
+
+        if apq == 0.0 {
+            return;
+        }
+
+        let theta = (a.data[q * n + q] - a.data[p * n + p]) / (2.0 * apq);
+        let sign = if theta >= 0.0 { 1.0 } else { -1.0 };
+        let t = sign / (theta.abs() + (theta * theta + 1.0).sqrt());
+        let c = 1.0 / (t * t + 1.0).sqrt();
+        let s = t * c;
+
+        for k in 0..n {
+            let akp = a.data[k * n + p];
+            let akq = a.data[k * n + q];
+            a.data[k * n + p] = c * akp - s * akq;
+            a.data[k * n + q] = s * akp + c * akq;
+        }
+
+        for k in 0..n {
+            let apk = a.data[p * n + k];
+            let aqk = a.data[q * n + k];
+            a.data[p * n + k] = c * apk - s * aqk;
+            a.data[q * n + k] = s * apk + c * aqk;
+        }
+
+        if compute_vectors {
+            for k in 0..n {
+                let vkp = v.data[k * n + p];
+                let vkq = v.data[k * n + q];
+                v.data[k * n + p] = c * vkp - s * vkq;
+                v.data[k * n + q] = s * vkp + c * vkq;
+            }
+        }
+    }
+
+    pub fn eigenvalues(&self) -> Vector {
+        // in ascending order
+        self.eigenvalues.clone()
+    }
+
+    pub fn eigenvectors(&self) -> Option<Matrix> {
+        // the j-th column goes with the j-th eigenvalue
+        self.eigenvectors.clone()
+    }
+}
+
+impl Decomposer {
+    pub fn symmetric_eigen(&self, matrix: &Matrix, error: f64, max_iter: usize) -> SymmetricEigen {
+        self.try_symmetric_eigen(matrix, error, max_iter)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_symmetric_eigen
+    (
+        &self,
+        matrix: &Matrix,
+        error: f64,
+        max_iter: usize,
+    ) -> Result<SymmetricEigen, LinalgError>
+    {
+        SymmetricEigen::new(matrix, error, max_iter, true)
+    }
+
+    pub fn symmetric_eigenvalues(&self, matrix: &Matrix, error: f64, max_iter: usize) -> Vector {
+        self.try_symmetric_eigenvalues(matrix, error, max_iter)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_symmetric_eigenvalues
+    (
+        &self,
+        matrix: &Matrix,
+        error: f64,
+        max_iter: usize,
+    ) -> Result<Vector, LinalgError>
+    {
+        Ok(SymmetricEigen::new(matrix, error, max_iter, false)?.eigenvalues)
+    }
+}
+
+pub struct GeneralEigen {
+    eigenvalues: Vec<Complex>,
+    eigenvectors: Option<Vec<Vec<Complex>>>,
+}
+
+impl GeneralEigen {
+    fn new
+    (
+        matrix: &Matrix,
+        max_iter: usize,
+        compute_vectors: bool,
+    ) -> Result<Self, LinalgError>
+    {
+        matrix.check_square()?;
+
+        let (q, hessenberg) = hessenberg_reduction(matrix, compute_vectors);
+        let (eigenvalues, eigenvectors) =
+            Self::shifted_qr(&hessenberg, compute_vectors.then_some(&q), max_iter)?;
+
+        Ok(Self {
+            eigenvalues,
+            eigenvectors,
+        })
+    }
+
+    fn shifted_qr
+    (
+        hessenberg: &Matrix,
+        q: Option<&Matrix>,
+        max_iter: usize,
+    ) -> Result<EigenPairs, LinalgError>
+    {
+        // francis double shift qr on an upper hessenberg matrix,
+        // deflating one 1x1 or 2x2 block at a time from the bottom.
+        // given the q of the hessenberg reduction, the whole real schur
+        // form T = Z^T A Z is kept and Z is accumulated starting from q,
+        // so the eigenvectors come from back substitution on T.
+        // a[i][j] is used with 1-based indexes like in the rest of the crate
+        let n = hessenberg.dimension().0;
+        let full = q.is_some();
+        let mut a = vec![vec![0.0; n + 1]; n + 1];
+        let mut schur = vec![vec![0.0; n + 1]; n + 1];
+
+        for i in 1..=n {
+            for j in 1..=n {
+                a[i][j] = hessenberg.data[(i - 1) * n + (j - 1)];
+
+                if let Some(q) = q {
+                    schur[i][j] = q.data[(i - 1) * n + (j - 1)];
+                }
+            }
+        }
+
+        let mut wr = vec![0.0; n + 1];
+        let mut wi = vec![0.0; n + 1];
+        let mut anorm = 0.0;
+
+        for i in 1..=n {
+            for j in i.saturating_sub(1).max(1)..=n {
+                anorm += a[i][j].abs();
+            }
+        }
+
+        let mut nn = n;
+        let mut t = 0.0;
+
+        while nn >= 1 {
+            let mut its = 0;
+
+            loop {
+                let mut l = nn;
+
+                while l >= 2 {
+                    let mut s = a[l - 1][l - 1].abs() + a[l][l].abs();
+
+                    if s == 0.0 {
+                        s = anorm;
+                    }
+
+                    if a[l][l - 1].abs() + s == s {
+                        a[l][l - 1] = 0.0;
+                        break;
+                    }
+
+                    l -= 1;
+                }
+
+                // rows and columns of T outside the active block are only
+                // updated when the schur form is needed
+                let (first_row, last_column) = if full { (1, n) } else { (l, nn) };
+                let mut x = a[nn][nn];
+
+                if l == nn {
+                    // one real eigenvalue found
+                    a[nn][nn] = x + t;
+                    wr[nn] = x + t;
+                    wi[nn] = 0.0;
+                    nn -= 1;
+                    break;
+                }
+
+                let mut y = a[nn - 1][nn - 1];
+                let mut w = a[nn][nn - 1] * a[nn - 1][nn];
+
+                if l == nn - 1 {
+                    // two eigenvalues from the trailing 2x2 block
+                    let p = 0.5 * (y - x);
+                    let q = p * p + w;
+                    let mut z = q.abs().sqrt();
+                    a[nn][nn] = x + t;
+                    a[nn - 1][nn - 1] = y + t;
+                    x += t;
+
+                    if q >= 0.0 {
+                        z = p + z.copysign(p);
+                        wr[nn - 1] = x + z;
+                        wr[nn] = x + z;
+
+                        if z != 0.0 {
+                            wr[nn] = x - w / z;
+                        }
+
+                        wi[nn - 1] = 0.0;
+                        wi[nn] = 0.0;
+
+                        if full {
+                            // a rotation makes the real 2x2 block triangular
+                            let x = a[nn][nn - 1];
+                            let s = x.abs() + z.abs();
+                            let (p, q) = (x / s, z / s);
+                            let r = (p * p + q * q).sqrt();
+                            let (p, q) = (p / r, q / r);
+
+                            for j in (nn - 1)..=n {
+                                let z = a[nn - 1][j];
+                                a[nn - 1][j] = q * z + p * a[nn][j];
+                                a[nn][j] = q * a[nn][j] - p * z;
+                            }
+
+                            for i in 1..=nn {
+                                let z = a[i][nn - 1];
+                                a[i][nn - 1] = q * z + p * a[i][nn];
+                                a[i][nn] = q * a[i][nn] - p * z;
+                            }
+
+                            for i in 1..=n {
+                                let z = schur[i][nn - 1];
+                                schur[i][nn - 1] = q * z + p * schur[i][nn];
+                                schur[i][nn] = q * schur[i][nn] - p * z;
+                            }
+                        }
+                    } else {
+                        // the upper row of a complex block keeps the
+                        // positive imaginary part
+                        wr[nn - 1] = x + p;
+                        wr[nn] = x + p;
+                        wi[nn - 1] = z;
+                        wi[nn] = -z;
+                    }
+
+                    nn = nn.saturating_sub(2);
+                    break;
+                }
+
+                if its == max_iter {
+                    return Err(LinalgError::NoConvergence { iterations: max_iter });
+                }
+
+                if its > 0 && its % 10 == 0 {
+                    // exceptional shift
+                    t += x;
+
+                    for i in 1..=nn {
+                        a[i][i] -= x;
+                    }
+
+                    let s = a[nn][nn - 1].abs() + a[nn - 1][nn - 2].abs();
+                    x = 0.75 * s;
+                    y = x;
+                    w = -0.4375 * s * s;
+                }
+
+                its += 1;
+
+                let mut m = nn - 2;
+                let (mut p, mut q, mut r);
+
+                loop {
+                    let z = a[m][m];
+                    let rr = x - z;
+                    let ss = y - z;
+                    p = (rr * ss - w) / a[m + 1][m] + a[m][m + 1];
+                    q = a[m + 1][m + 1] - z - rr - ss;
+                    r = a[m + 2][m + 1];
+
+                    let s = p.abs() + q.abs() + r.abs();
+                    p /= s;
+                    q /= s;
+                    r /= s;
+
+                    if m == l {
+                        break;
+                    }
+
+                    let u = a[m][m - 1].abs() * (q.abs() + r.abs());
+                    let v = p.abs() * (a[m - 1][m - 1].abs() + z.abs() + a[m + 1][m + 1].abs());
+
+                    if u + v == v {
+                        break;
+                    }
+
+                    m -= 1;
+                }
+
+                for i in (m + 2)..=nn {
+                    a[i][i - 2] = 0.0;
+
+                    if i != m + 2 {
+                        a[i][i - 3] = 0.0;
+                    }
+                }
+
+                for k in m..nn {
+                    if k != m {
+                        p = a[k][k - 1];
+                        q = a[k + 1][k - 1];
+                        r = 0.0;
+
+                        if k != nn - 1 {
+                            r = a[k + 2][k - 1];
+                        }
+
+                        x = p.abs() + q.abs() + r.abs();
+
+                        if x != 0.0 {
+                            p /= x;
+                            q /= x;
+                            r /= x;
+                        }
+                    }
+
+                    let s = (p * p + q * q + r * r).sqrt().copysign(p);
+
+                    if s == 0.0 {
+                        continue;
+                    }
+
+                    if k == m {
+                        if l != m {
+                            a[k][k - 1] = -a[k][k - 1];
+                        }
+                    } else {
+                        a[k][k - 1] = -s * x;
+                    }
+
+                    p += s;
+                    x = p / s;
+                    y = q / s;
+                    let z = r / s;
+                    q /= p;
+                    r /= p;
+
+                    for j in k..=last_column {
+                        let mut p = a[k][j] + q * a[k + 1][j];
+
+                        if k != nn - 1 {
+                            p += r * a[k + 2][j];
+                            a[k + 2][j] -= p * z;
+                        }
+
+                        a[k + 1][j] -= p * y;
+                        a[k][j] -= p * x;
+                    }
+
+                    for i in first_row..=min(nn, k + 3) {
+                        let mut p = x * a[i][k] + y * a[i][k + 1];
+
+                        if k != nn - 1 {
+                            p += z * a[i][k + 2];
+                            a[i][k + 2] -= p * r;
+                        }
+
+                        a[i][k + 1] -= p * q;
+                        a[i][k] -= p;
+                    }
+
+                    if full {
+                        for row in schur.iter_mut().skip(1) {
+                            let mut p = x * row[k] + y * row[k + 1];
+
+                            if k != nn - 1 {
+                                p += z * row[k + 2];
+                                row[k + 2] -= p * r;
+                            }
+
+                            row[k + 1] -= p * q;
+                            row[k] -= p;
+                        }
+                    }
+                }
+
+                if l + 1 >= nn {
+                    break;
+                }
+            }
+        }
+
+        let eigenvalues = (1..=n).map(|i| Complex::new(wr[i], wi[i])).collect::<Vec<Complex>>();
+
+        if !full {
+            return Ok((eigenvalues, None));
+        }
+
+        Self::back_substitution(&mut a, &wr, &wi, anorm);
+
+        // eigenvectors of A are Z times those of T. T is upper triangular
+        // apart from the 2x2 blocks, so going from the last column down
+        // only reads columns of Z that weren't overwritten yet
+        for j in (1..=n).rev() {
+            for row in schur.iter_mut().skip(1) {
+                row[j] = (1..=j).map(|k| row[k] * a[k][j]).sum();
+            }
+        }
+
+        // a complex pair shares two columns, real and imaginary parts of
+        // the vector of the eigenvalue with positive imaginary part
+        let eigenvectors = (1..=n).map(|j| {
+            let vector = (1..=n).map(|i| {
+                if wi[j] > 0.0 {
+                    Complex::new(schur[i][j], schur[i][j + 1])
+                } else if wi[j] < 0.0 {
+                    Complex::new(schur[i][j - 1], -schur[i][j])
+                } else {
+                    Complex::new(schur[i][j], 0.0)
+                }
+            }).collect::<Vec<Complex>>();
+
+            let length = vector.iter().map(|c| c.norm() * c.norm()).sum::<f64>().sqrt();
+
+            if length == 0.0 {
+                return vector;
+            }
+
+            vector.iter().map(|c| c * (1.0 / length)).collect()
+        }).collect::<Vec<Vec<Complex>>>();
+
+        if is_defective(&eigenvectors) {
+            return Ok((eigenvalues, None));
+        }
+
+        Ok((eigenvalues, Some(eigenvectors)))
+    }
+
+    fn back_substitution(a: &mut [Vec<f64>], wr: &[f64], wi: &[f64], anorm: f64) {
+        // overwrites the schur form T with the eigenvectors of T, one column
+        // per eigenvalue, solving (T - lambda I) x = 0 from the bottom up.
+        // a zero diagonal difference is replaced by eps |T|, so repeated
+        // eigenvalues of a non defective T still give independent vectors
+        let n = wr.len() - 1;
+        let eps = f64::EPSILON;
+
+        if anorm == 0.0 {
+            return;
+        }
+
+        for nn in (1..=n).rev() {
+            let p = wr[nn];
+            let q = wi[nn];
+            let (mut z, mut r, mut s) = (0.0, 0.0, 0.0);
+
+            if q == 0.0 {
+                // real vector
+                let mut l = nn;
+                a[nn][nn] = 1.0;
+
+                for i in (1..nn).rev() {
+                    let w = a[i][i] - p;
+                    let ra = (l..=nn).map(|j| a[i][j] * a[j][nn]).sum::<f64>();
+
+                    if wi[i] < 0.0 {
+                        z = w;
+                        s = ra;
+                        continue;
+                    }
+
+                    l = i;
+
+                    if wi[i] == 0.0 {
+                        a[i][nn] = if w != 0.0 { -ra / w } else { -ra / (eps * anorm) };
+                    } else {
+                        // upper row of a complex 2x2 block
+                        let x = a[i][i + 1];
+                        let y = a[i + 1][i];
+                        let q = (wr[i] - p) * (wr[i] - p) + wi[i] * wi[i];
+                        let t = (x * s - z * ra) / q;
+                        a[i][nn] = t;
+                        a[i + 1][nn] = if x.abs() > z.abs() {
+                            (-ra - w * t) / x
+                        } else {
+                            (-s - y * t) / z
+                        };
+                    }
+
+                    let t = a[i][nn].abs();
+
+                    if eps * t * t > 1.0 {
+                        for row in a.iter_mut().take(nn + 1).skip(i) {
+                            row[nn] /= t;
+                        }
+                    }
+                }
+            } else if q < 0.0 {
+                // complex vector of wr + i wi, stored in columns nn - 1 and nn
+                let mut l = nn - 1;
+
+                if a[nn][nn - 1].abs() > a[nn - 1][nn].abs() {
+                    a[nn - 1][nn - 1] = q / a[nn][nn - 1];
+                    a[nn - 1][nn] = -(a[nn][nn] - p) / a[nn][nn - 1];
+                } else {
+                    let c = Complex::new(0.0, -a[nn - 1][nn])
+                            / Complex::new(a[nn - 1][nn - 1] - p, q);
+                    a[nn - 1][nn - 1] = c.real();
+                    a[nn - 1][nn] = c.imaginary();
+                }
+
+                a[nn][nn - 1] = 0.0;
+                a[nn][nn] = 1.0;
+
+                for i in (1..(nn - 1)).rev() {
+                    let ra = (l..=nn).map(|j| a[i][j] * a[j][nn - 1]).sum::<f64>();
+                    let sa = (l..=nn).map(|j| a[i][j] * a[j][nn]).sum::<f64>();
+                    let w = a[i][i] - p;
+
+                    if wi[i] < 0.0 {
+                        z = w;
+                        r = ra;
+                        s = sa;
+                        continue;
+                    }
+
+                    l = i;
+
+                    if wi[i] == 0.0 {
+                        let c = Complex::new(-ra, -sa) / Complex::new(w, q);
+                        a[i][nn - 1] = c.real();
+                        a[i][nn] = c.imaginary();
+                    } else {
+                        // upper row of another complex 2x2 block
+                        let x = a[i][i + 1];
+                        let y = a[i + 1][i];
+                        let mut vr = (wr[i] - p) * (wr[i] - p) + wi[i] * wi[i] - q * q;
+                        let vi = (wr[i] - p) * 2.0 * q;
+
+                        if vr == 0.0 && vi == 0.0 {
+                            vr = eps * anorm * (w.abs() + q.abs() + x.abs() + y.abs() + z.abs());
+                        }
+
+                        let c = Complex::new(x * r - z * ra + q * sa, x * s - z * sa - q * ra)
+                                / Complex::new(vr, vi);
+                        a[i][nn - 1] = c.real();
+                        a[i][nn] = c.imaginary();
+
+                        if x.abs() > z.abs() + q.abs() {
+                            a[i + 1][nn - 1] = (-ra - w * a[i][nn - 1] + q * a[i][nn]) / x;
+                            a[i + 1][nn] = (-sa - w * a[i][nn] - q * a[i][nn - 1]) / x;
+                        } else {
+                            let c = Complex::new(-r - y * a[i][nn - 1], -s - y * a[i][nn])
+                                    / Complex::new(z, q);
+                            a[i + 1][nn - 1] = c.real();
+                            a[i + 1][nn] = c.imaginary();
+                        }
+                    }
+
+                    let t = a[i][nn - 1].abs().max(a[i][nn].abs());
+
+                    if eps * t * t > 1.0 {
+                        for row in a.iter_mut().take(nn + 1).skip(i) {
+                            row[nn - 1] /= t;
+                            row[nn] /= t;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn eigenvalues(&self) -> Vec<Complex> {
+        // complex eigenvalues come in adjacent conjugate pairs
+        self.eigenvalues.clone()
+    }
+
+    pub fn eigenvectors(&self) -> Option<Vec<Vec<Complex>>> {
+        // the j-th vector goes with the j-th eigenvalue, normalized
+        // to unit length. None for a (numerically) defective matrix,
+        // which has no basis of eigenvectors
+        self.eigenvectors.clone()
+    }
+}
+
+fn is_defective(vectors: &[Vec<Complex>]) -> bool {
+    // unit eigenvectors that are parallel up to rounding belong to a
+    // repeated eigenvalue without enough independent eigenvectors
+    let threshold = 1.0 - f64::EPSILON.sqrt();
+
+    for (i, u) in vectors.iter().enumerate() {
+        for v in vectors.iter().skip(i + 1) {
+            let product = u.iter()
+                          .zip(v.iter())
+                          .fold(Complex::new(0.0, 0.0), |sum, (a, b)| sum + a.conjugate() * *b);
+
+            if product.norm() > threshold {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+fn hessenberg_reduction(matrix: &Matrix, compute_q: bool) -> (Matrix, Matrix) {
+    // A = Q H Q^T using householder reflections on the columns below
+    // the first subdiagonal
+    let n = matrix.dimension().0;
+    let mut h = matrix.clone();
+    let mut q = Matrix::identity(n);
+
+    for k in 0..n.saturating_sub(2) {
+        let mut v = ((k + 1)..n).map(|i| h.data[i * n + k]).collect::<Vec<f64>>();
+        let alpha = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+
+        if alpha == 0.0 {
+            continue;
+        }
+
+        v[0] += alpha.copysign(v[0]);
+        let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+
+        for x in v.iter_mut() {
+            *x /= norm;
+        }
+
+        // H = (I - 2 v v^T) H
+        for j in 0..n {
+            let dot = v.iter().enumerate()
+                      .map(|(i, vi)| vi * h.data[(k + 1 + i) * n + j])
+                      .sum::<f64>();
+
+            for (i, vi) in v.iter().enumerate() {
+                h.data[(k + 1 + i) * n + j] -= 2.0 * vi * dot;
+            }
+        }
+
+        // H = H (I - 2 v v^T), and the same for Q
+        let mut targets = vec![&mut h];
+
+        if compute_q {
+            targets.push(&mut q);
+        }
+
+        for target in targets {
+            for i in 0..n {
+                let dot = v.iter().enumerate()
+                          .map(|(j, vj)| vj * target.data[i * n + k + 1 + j])
+                          .sum::<f64>();
+
+                for (j, vj) in v.iter().enumerate() {
+                    target.data[i * n + k + 1 + j] -= 2.0 * vj * dot;
+                }
+            }
+        }
+
+        for i in (k + 2)..n {
+            h.data[i * n + k] = 0.0;
+        }
+    }
+
+    (q, h)
+}
+
+impl Decomposer {
+    pub fn hessenberg(&self, matrix: &Matrix) -> (Matrix, Matrix) {
+        self.try_hessenberg(matrix).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_hessenberg(&self, matrix: &Matrix) -> Result<(Matrix, Matrix), LinalgError> {
+        // returns (Q, H) with A = Q H Q^T and H upper hessenberg
+        matrix.check_square()?;
+
+        Ok(hessenberg_reduction(matrix, true))
+    }
+
+    pub fn eigen(&self, matrix: &Matrix, max_iter: usize) -> GeneralEigen {
+        self.try_eigen(matrix, max_iter).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_eigen(&self, matrix: &Matrix, max_iter: usize) -> Result<GeneralEigen, LinalgError> {
+        // max_iter is the number of qr iterations allowed per eigenvalue
+        GeneralEigen::new(matrix, max_iter, true)
+    }
+
+    pub fn eigenvalues(&self, matrix: &Matrix, max_iter: usize) -> Vec<Complex> {
+        self.try_eigenvalues(matrix, max_iter).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_eigenvalues
+    (
+        &self,
+        matrix: &Matrix,
+        max_iter: usize,
+    ) -> Result<Vec<Complex>, LinalgError>
+    {
+        Ok(GeneralEigen::new(matrix, max_iter, false)?.eigenvalues)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // largest |(A v - lambda v)_i| over the computed eigenpairs
+    fn eigen_residual(a: &Matrix, eigen: &GeneralEigen) -> f64 {
+        let n = a.dimension().0;
+        let vectors = eigen.eigenvectors().unwrap();
+        let mut largest: f64 = 0.0;
+
+        for (lambda, v) in eigen.eigenvalues().iter().zip(vectors.iter()) {
+            for i in 0..n {
+                let mut av = Complex::new(0.0, 0.0);
+
+                for j in 0..n {
+                    av = av + Complex::new(a.data[i * n + j], 0.0) * v[j];
+                }
+
+                largest = largest.max((av - *lambda * v[i]).norm());
+            }
+        }
+
+        largest
+    }
+
+    fn sorted(mut values: Vec<Complex>) -> Vec<Complex> {
+        values.sort_by(|x, y| (x.real(), x.imaginary()).partial_cmp(&(y.real(), y.imaginary())).unwrap());
+        values
+    }
+
+    #[test]
+    fn rotation_has_complex_conjugate_eigenvalues() {
+        let (c, s) = (0.6, 0.8);
+        let a = Matrix::new(&[&[c, -s], &[s, c]]);
+        let eigen = Decomposer::new().eigen(&a, 100);
+        let values = sorted(eigen.eigenvalues());
+
+        assert!((values[0] - Complex::new(c, -s)).norm() < 1e-12);
+        assert!((values[1] - Complex::new(c, s)).norm() < 1e-12);
+        assert!(eigen_residual(&a, &eigen) < 1e-10);
+    }
+
+    #[test]
+    fn eigenpairs_of_a_real_matrix() {
+        // eigenvalues 2 +- i sqrt(7) and 3 +- 1 / sqrt(2)
+        let a = Matrix::new(&[&[4.0, -2.0, 1.0, 0.5],
+                              &[3.0, 1.0, -1.0, 2.0],
+                              &[0.0, 2.0, 3.0, -1.0],
+                              &[1.0, 0.0, 1.0, 2.0]]);
+        let eigen = Decomposer::new().eigen(&a, 100);
+        let values = eigen.eigenvalues();
+        let sum = values.iter().fold(Complex::new(0.0, 0.0), |s, x| s + *x);
+        let product = values.iter().fold(Complex::new(1.0, 0.0), |p, x| p * *x);
+
+        let expected = [Complex::new(2.0, -7f64.sqrt()), Complex::new(2.0, 7f64.sqrt()),
+                        Complex::new(3.0 - 0.5f64.sqrt(), 0.0), Complex::new(3.0 + 0.5f64.sqrt(), 0.0)];
+
+        for y in expected.iter() {
+            assert!(values.iter().any(|x| (*x - *y).norm() < 1e-10));
+        }
+
+        assert!((sum - Complex::new(10.0, 0.0)).norm() < 1e-10);
+        assert!((product - Complex::new(a.determinant(), 0.0)).norm() < 1e-9);
+        assert!(eigen_residual(&a, &eigen) < 1e-9);
+        assert_eq!(sorted(Decomposer::new().eigenvalues(&a, 100)), sorted(values));
+    }
+
+    #[test]
+    fn defective_jordan_block() {
+        let a = Matrix::new(&[&[2.0, 1.0, 0.0], &[0.0, 2.0, 1.0], &[0.0, 0.0, 2.0]]);
+        let eigen = Decomposer::new().eigen(&a, 100);
+
+        for lambda in eigen.eigenvalues() {
+            assert!((lambda - Complex::new(2.0, 0.0)).norm() < 1e-4);
+        }
+
+        // the only eigenvector is e1, so there is no basis to return
+        assert!(eigen.eigenvectors().is_none());
+
+        // the same jordan block in another basis
+        let s = Matrix::new(&[&[1.0, 2.0, 0.0], &[0.0, 1.0, 1.0], &[1.0, 0.0, 3.0]]);
+        let similar = &(&s * &a) * &s.inverse();
+
+        assert!(Decomposer::new().eigen(&similar, 100).eigenvectors().is_none());
+    }
+
+    #[test]
+    fn repeated_eigenvalues_get_independent_eigenvectors() {
+        // S diag(2, 2, 3) S^-1 is not symmetric but has a full eigenbasis
+        let s = Matrix::new(&[&[1.0, 2.0, 0.0], &[0.0, 1.0, 1.0], &[1.0, 0.0, 3.0]]);
+        let d = Matrix::new(&[&[2.0, 0.0, 0.0], &[0.0, 2.0, 0.0], &[0.0, 0.0, 3.0]]);
+        let a = &(&s * &d) * &s.inverse();
+        let eigen = Decomposer::new().eigen(&a, 100);
+        let vectors = eigen.eigenvectors().unwrap();
+
+        assert!(eigen_residual(&a, &eigen) < 1e-12);
+
+        // the vectors are real, and a basis when their determinant is away from zero
+        let mut basis = Matrix::zero(3, 3);
+
+        for (j, v) in vectors.iter().enumerate() {
+            for i in 0..3 {
+                assert!(v[i].imaginary().abs() < 1e-14);
+                basis.data[i * 3 + j] = v[i].real();
+            }
+        }
+
+        assert!(basis.determinant().abs() > 0.1);
+
+        let identity = Decomposer::new().eigen(&Matrix::identity(4), 100);
+
+        assert!(eigen_residual(&Matrix::identity(4), &identity) < 1e-15);
+        assert!(!is_defective(&identity.eigenvectors().unwrap()));
+    }
+
+    #[test]
+    fn eigenpairs_of_a_larger_matrix() {
+        let n = 40;
+        let data = (0..n * n).map(|k| ((k * 37 + k / n * 17) % 23) as f64 / 7.0 - 1.5).collect();
+        let a = Matrix::from_data(n, n, data);
+        let eigen = Decomposer::new().eigen(&a, 100);
+        let values = eigen.eigenvalues();
+        let trace = (0..n).map(|i| a.data[i * n + i]).sum::<f64>();
+        let sum = values.iter().fold(Complex::new(0.0, 0.0), |s, x| s + *x);
+
+        assert!(values.iter().filter(|x| x.imaginary() != 0.0).count() >= 4);
+        assert!((sum - Complex::new(trace, 0.0)).norm() < 1e-10);
+        assert!(eigen_residual(&a, &eigen) < 1e-11);
+    }
+
+    fn max_difference(a: &Matrix, b: &Matrix) -> f64 {
+        a.data.iter().zip(b.data.iter()).map(|(x, y)| (x - y).abs()).fold(0.0, f64::max)
+    }
+
+    fn check_symmetric(a: &Matrix) -> Vector {
+        let n = a.dimension().0;
+        let eigen = Decomposer::new().symmetric_eigen(a, 1e-14, 100);
+        let values = eigen.eigenvalues();
+        let v = eigen.eigenvectors().unwrap();
+        let mut lambda = Matrix::zero(n, n);
+
+        for i in 0..n {
+            lambda.data[i * n + i] = values.components[i];
+        }
+
+        assert!(values.components.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(max_difference(&(&v.transpose() * &v), &Matrix::identity(n)) < 1e-13);
+        assert!(max_difference(&(a * &v), &(&v * &lambda)) < 1e-12);
+        assert_eq!(Decomposer::new().symmetric_eigenvalues(a, 1e-14, 100), values);
+
+        values
+    }
+
+    #[test]
+    fn symmetric_eigenpairs() {
+        let values = check_symmetric(&Matrix::new(&[&[2.0, 1.0], &[1.0, 2.0]]));
+
+        assert!((&values - &Vector::new(&[1.0, 3.0])).components.iter().all(|d| d.abs() < 1e-14));
+
+        let a = Matrix::new(&[&[4.0, 1.0, -2.0, 2.0],
+                              &[1.0, 2.0, 0.0, 1.0],
+                              &[-2.0, 0.0, 3.0, -2.0],
+                              &[2.0, 1.0, -2.0, -1.0]]);
+        let values = check_symmetric(&a);
+        let sum = values.components.iter().sum::<f64>();
+        let product = values.components.iter().product::<f64>();
+
+        assert!((sum - 8.0).abs() < 1e-12);
+        assert!((product - a.determinant()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn symmetric_eigenpairs_with_repeated_eigenvalues() {
+        // I + J, with J the matrix of ones: eigenvalues 1, 1, 1 and 5
+        let a = Matrix::new(&[&[2.0, 1.0, 1.0, 1.0],
+                              &[1.0, 2.0, 1.0, 1.0],
+                              &[1.0, 1.0, 2.0, 1.0],
+                              &[1.0, 1.0, 1.0, 2.0]]);
+        let values = check_symmetric(&a);
+
+        let expected = Vector::new(&[1.0, 1.0, 1.0, 5.0]);
+
+        assert!((&values - &expected).components.iter().all(|d| d.abs() < 1e-13));
+
+        let values = check_symmetric(&Matrix::identity(3));
+
+        assert_eq!(values.components, vec![1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn symmetric_eigen_errors() {
+        let decomposer = Decomposer::new();
+        let a = Matrix::new(&[&[1.0, 2.0], &[2.0, 1.0]]);
+        let upper = Matrix::new(&[&[1.0, 2.0], &[0.0, 1.0]]);
+
+        assert!(matches!(decomposer.try_symmetric_eigen(&upper, 1e-14, 100),
+                         Err(LinalgError::NotSymmetric)));
+        assert!(matches!(decomposer.try_symmetric_eigen(&a, 1e-14, 0),
+                         Err(LinalgError::NoConvergence { iterations: 0 })));
+        assert!(matches!(decomposer.try_symmetric_eigen(&Matrix::zero(2, 3), 1e-14, 100),
+                         Err(LinalgError::NotSquare { .. })));
+    }
+}
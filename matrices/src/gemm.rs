@@ -0,0 +1,179 @@
+use crate::*;
+
+// block sizes of the multiplication kernel. each thread owns MC rows of C
+// and updates them with KC rows of B at a time, NC columns wide, so the
+// pieces of B and C being reused stay in cache
+const MC: usize = 64;
+const KC: usize = 256;
+const NC: usize = 1024;
+
+// C += alpha A B, with A m x k, B k x n and C m x n, all row-major.
+// the innermost loop runs over contiguous rows of B and C, which the
+// compiler can vectorize for f32 and f64
+fn kernel<T: Scalar>
+(
+    alpha: &T,
+    a: &[T],
+    b: &[T],
+    c: &mut [T],
+    (m, k, n): (usize, usize, usize),
+)
+{
+    if m == 0 || n == 0 {
+        return;
+    }
+
+    c.par_chunks_mut(MC * n).enumerate().for_each(|(block, c_block)| {
+        let first_row = block * MC;
+
+        for kk in (0..k).step_by(KC) {
+            let k_end = min(kk + KC, k);
+
+            for jj in (0..n).step_by(NC) {
+                let j_end = min(jj + NC, n);
+
+                for (i, c_row) in c_block.chunks_exact_mut(n).enumerate() {
+                    let a_row = &a[(first_row + i) * k..(first_row + i + 1) * k];
+                    let c_row = &mut c_row[jj..j_end];
+
+                    for p in kk..k_end {
+                        let factor = alpha.clone() * a_row[p].clone();
+                        let b_row = &b[p * n + jj..p * n + j_end];
+
+                        for (r, x) in c_row.iter_mut().zip(b_row.iter()) {
+                            *r = r.clone() + factor.clone() * x.clone();
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
+
+impl<T: Scalar> Matrix<T> {
+    pub fn gemm(alpha: T, a: &Matrix<T>, b: &Matrix<T>, beta: T, c: &mut Matrix<T>) {
+        Self::try_gemm(alpha, a, b, beta, c).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_gemm
+    (
+        alpha: T,
+        a: &Matrix<T>,
+        b: &Matrix<T>,
+        beta: T,
+        c: &mut Matrix<T>,
+    ) -> Result<(), LinalgError>
+    {
+        // C = alpha A B + beta C in place. like blas, beta = 0 ignores
+        // the previous contents of C, even if they aren't finite
+        let (r1, c1) = a.dimension();
+        let (r2, c2) = b.dimension();
+
+        if c1 != r2 {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (r1, c1),
+                found: (r2, c2),
+            });
+        }
+
+        if c.dimension() != (r1, c2) {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (r1, c2),
+                found: c.dimension(),
+            });
+        }
+
+        if beta.is_zero() {
+            c.data.iter_mut().for_each(|x| *x = T::zero());
+        } else if beta != T::one() {
+            c.data.par_iter_mut().for_each(|x| *x = beta.clone() * x.clone());
+        }
+
+        kernel(&alpha, &a.data, &b.data, &mut c.data, (r1, c1, c2));
+
+        Ok(())
+    }
+
+    pub(crate) fn blocked_product(&self, other: &Self) -> Self {
+        // dimensions are checked by the caller
+        let (m, k) = self.dimension();
+        let n = other.dimension().1;
+        let mut data = vec![T::zero(); m * n];
+
+        kernel(&T::one(), &self.data, &other.data, &mut data, (m, k, n));
+
+        Self::from_data(m, n, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // small integer entries keep every product and sum exact
+    fn sample(rows: usize, columns: usize, seed: usize) -> Matrix {
+        let data = (0..rows * columns).map(|i| ((i * 7 + seed * 13) % 11) as f64 - 5.0).collect();
+
+        Matrix::from_data(rows, columns, data)
+    }
+
+    fn naive_product(a: &Matrix, b: &Matrix) -> Matrix {
+        let ((m, k), n) = (a.dimension(), b.dimension().1);
+        let mut data = vec![0.0; m * n];
+
+        for i in 0..m {
+            for j in 0..n {
+                data[i * n + j] = (0..k).map(|p| a.data[i * k + p] * b.data[p * n + j]).sum();
+            }
+        }
+
+        Matrix::from_data(m, n, data)
+    }
+
+    #[test]
+    fn blocked_product_matches_naive_product() {
+        let shapes = [(1, 1, 1), (1, 7, 1), (1, 300, 1100), (5, 3, 2), (64, 256, 1024),
+                      (65, 257, 1025), (130, 5, 3), (3, 513, 4)];
+
+        for (seed, &(m, k, n)) in shapes.iter().enumerate() {
+            let a = sample(m, k, seed);
+            let b = sample(k, n, seed + 1);
+
+            assert_eq!(a.blocked_product(&b), naive_product(&a, &b), "{}x{} * {}x{}", m, k, k, n);
+            assert_eq!(&a * &b, naive_product(&a, &b));
+        }
+    }
+
+    #[test]
+    fn gemm_scales_and_accumulates() {
+        let a = sample(65, 257, 1);
+        let b = sample(257, 70, 2);
+        let c = sample(65, 70, 3);
+        let ab = naive_product(&a, &b);
+
+        let mut result = c.clone();
+        Matrix::gemm(2.0, &a, &b, 0.0, &mut result);
+        assert_eq!(result, &ab * 2.0);
+
+        let mut result = c.clone();
+        Matrix::gemm(2.0, &a, &b, -3.0, &mut result);
+        assert_eq!(result, &(&ab * 2.0) + &(&c * -3.0));
+
+        // beta = 0 ignores non finite entries already in C
+        let mut result = Matrix::from_data(65, 70, vec![f64::NAN; 65 * 70]);
+        Matrix::gemm(1.0, &a, &b, 0.0, &mut result);
+        assert_eq!(result, ab);
+    }
+
+    #[test]
+    fn gemm_dimension_checks() {
+        let a = sample(2, 3, 0);
+        let b = sample(4, 5, 0);
+        let mut c = sample(2, 5, 0);
+
+        assert_eq!(Matrix::try_gemm(1.0, &a, &b, 0.0, &mut c),
+                   Err(LinalgError::DimensionMismatch { expected: (2, 3), found: (4, 5) }));
+        assert_eq!(Matrix::try_gemm(1.0, &a, &sample(3, 4, 0), 0.0, &mut c),
+                   Err(LinalgError::DimensionMismatch { expected: (2, 4), found: (2, 5) }));
+    }
+}
@@ -0,0 +1,495 @@
+use crate::*;
+use std::cmp::Ordering;
+
+// plane rotation G = [c s; -s c] acting on two coordinates i and j
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Givens {
+    c: f64,
+    s: f64,
+}
+
+impl Givens {
+    // the rotation with G [a; b] = [r; 0], r = hypot(a, b)
+    pub fn new(a: f64, b: f64) -> Self {
+        if b == 0.0 {
+            return Self { c: 1.0, s: 0.0 };
+        }
+
+        let r = a.hypot(b);
+
+        Self {
+            c: a / r,
+            s: b / r,
+        }
+    }
+
+    pub fn c(&self) -> f64 {
+        self.c
+    }
+
+    pub fn s(&self) -> f64 {
+        self.s
+    }
+
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        (self.c * x + self.s * y, -self.s * x + self.c * y)
+    }
+
+    // rows i and j of the matrix become G [row i; row j]
+    pub fn rotate_rows(&self, matrix: &mut Matrix, i: usize, j: usize) {
+        let n = matrix.dimension().1;
+
+        for k in 0..n {
+            let (x, y) = self.apply(matrix.data[(i - 1) * n + k], matrix.data[(j - 1) * n + k]);
+            matrix.data[(i - 1) * n + k] = x;
+            matrix.data[(j - 1) * n + k] = y;
+        }
+    }
+
+    // matrix = matrix G^T on columns i and j, so that Q R = (Q G^T) (G R)
+    pub fn rotate_columns(&self, matrix: &mut Matrix, i: usize, j: usize) {
+        let n = matrix.dimension().1;
+
+        for row in matrix.data.chunks_exact_mut(n) {
+            let (x, y) = self.apply(row[i - 1], row[j - 1]);
+            row[i - 1] = x;
+            row[j - 1] = y;
+        }
+    }
+}
+
+// full qr factorization A = Q R (Q m x m, R m x n) that can follow changes
+// of A without being recomputed. every update costs O(m^2 + m n)
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdatableQr {
+    q: Matrix,
+    r: Matrix,
+}
+
+impl UpdatableQr {
+    pub fn new(q: Matrix, r: Matrix) -> Self {
+        Self::try_new(q, r).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_new(q: Matrix, r: Matrix) -> Result<Self, LinalgError> {
+        q.check_square()?;
+
+        let (m, n) = r.dimension();
+
+        if q.dimension().0 != m {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (m, m),
+                found: q.dimension(),
+            });
+        }
+
+        Ok(Self { q, r })
+    }
+
+    pub fn q(&self) -> &Matrix {
+        &self.q
+    }
+
+    pub fn r(&self) -> &Matrix {
+        &self.r
+    }
+
+    // dimension of A
+    pub fn dimension(&self) -> (usize, usize) {
+        self.r.dimension()
+    }
+
+    // zeroes the subdiagonal of an upper hessenberg R, columns from..n
+    fn retriangularize(&mut self, from: usize) {
+        let (m, n) = self.r.dimension();
+
+        for k in from..=min(n, m - 1) {
+            let rotation = Givens::new(self.r.data[(k - 1) * n + k - 1], self.r.data[k * n + k - 1]);
+
+            rotation.rotate_rows(&mut self.r, k, k + 1);
+            rotation.rotate_columns(&mut self.q, k, k + 1);
+            self.r.data[k * n + k - 1] = 0.0;
+        }
+    }
+
+    pub fn rank_one_update(&mut self, u: &Vector, v: &Vector) {
+        self.try_rank_one_update(u, v).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_rank_one_update(&mut self, u: &Vector, v: &Vector) -> Result<(), LinalgError> {
+        // A + u v^T = Q (R + w v^T) with w = Q^T u. rotations from the
+        // bottom turn w into |w| e_1 and R into upper hessenberg, which
+        // stays hessenberg after adding the rank one term to its first row
+        let (m, n) = self.r.dimension();
+
+        if u.size() != m || v.size() != n {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (m, n),
+                found: (u.size(), v.size()),
+            });
+        }
+
+        let mut w = self.q.transpose().try_mul_vector(u)?.components;
+
+        for k in (1..m).rev() {
+            let rotation = Givens::new(w[k - 1], w[k]);
+            let (top, _) = rotation.apply(w[k - 1], w[k]);
+            w[k - 1] = top;
+            w[k] = 0.0;
+
+            rotation.rotate_rows(&mut self.r, k, k + 1);
+            rotation.rotate_columns(&mut self.q, k, k + 1);
+        }
+
+        for (j, vj) in v.components.iter().enumerate() {
+            self.r.data[j] += w[0] * vj;
+        }
+
+        self.retriangularize(1);
+
+        Ok(())
+    }
+
+    pub fn append_row(&mut self, row: &Vector) {
+        let m = self.r.dimension().0;
+
+        self.insert_row(m + 1, row)
+    }
+
+    pub fn insert_row(&mut self, position: usize, row: &Vector) {
+        self.try_insert_row(position, row).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_insert_row(&mut self, position: usize, row: &Vector) -> Result<(), LinalgError> {
+        // the new row goes first: [row; A] = diag(1, Q) [row; R], and
+        // [row; R] is upper hessenberg. the rows of the new Q are then
+        // put back in the order of A
+        let (m, n) = self.r.dimension();
+
+        if position == 0 || position > m + 1 {
+            return Err(LinalgError::IndexOutOfBounds {
+                index: (position, 1),
+                dimension: (m + 1, n),
+            });
+        }
+
+        if row.size() != n {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (1, n),
+                found: row.dimension(),
+            });
+        }
+
+        let mut q = Matrix::zero(m + 1, m + 1);
+
+        for i in 0..=m {
+            let target = &mut q.data[i * (m + 1)..(i + 1) * (m + 1)];
+
+            match (i + 1).cmp(&position) {
+                Ordering::Less => target[1..].copy_from_slice(self.q.row_slice(i + 1)),
+                Ordering::Equal => target[0] = 1.0,
+                Ordering::Greater => target[1..].copy_from_slice(self.q.row_slice(i)),
+            }
+        }
+
+        let mut r = row.components.clone();
+        r.extend_from_slice(&self.r.data);
+
+        self.q = q;
+        self.r = Matrix::from_data(m + 1, n, r);
+        self.retriangularize(1);
+
+        Ok(())
+    }
+
+    pub fn delete_row(&mut self, position: usize) {
+        self.try_delete_row(position).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_delete_row(&mut self, position: usize) -> Result<(), LinalgError> {
+        // rotations from the bottom reduce row `position` of Q to +-e_1^T,
+        // so that row of A only depends on the first row of R. dropping
+        // both leaves the qr of the other rows, with R still triangular
+        let (m, n) = self.r.dimension();
+
+        if position == 0 || position > m {
+            return Err(LinalgError::IndexOutOfBounds {
+                index: (position, 1),
+                dimension: (m, n),
+            });
+        }
+
+        if m == 1 {
+            return Err(LinalgError::Empty);
+        }
+
+        for k in (1..m).rev() {
+            let (a, b) = (self.q.data[(position - 1) * m + k - 1], self.q.data[(position - 1) * m + k]);
+            let rotation = Givens::new(a, b);
+
+            rotation.rotate_rows(&mut self.r, k, k + 1);
+            rotation.rotate_columns(&mut self.q, k, k + 1);
+        }
+
+        let q = (1..=m).filter(|&i| i != position)
+                .flat_map(|i| self.q.row_slice(i)[1..].to_vec())
+                .collect::<Vec<f64>>();
+
+        let mut r = self.r.data[n..].to_vec();
+
+        // clean the rounding left below the diagonal
+        for i in 0..(m - 1) {
+            for j in 0..min(i, n) {
+                r[i * n + j] = 0.0;
+            }
+        }
+
+        self.q = Matrix::from_data(m - 1, m - 1, q);
+        self.r = Matrix::from_data(m - 1, n, r);
+
+        Ok(())
+    }
+
+    pub fn solve_least_squares(&self, b: &Vector) -> Vector {
+        self.try_solve_least_squares(b).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_solve_least_squares(&self, b: &Vector) -> Result<Vector, LinalgError> {
+        // needs m >= n and R with a nonzero diagonal
+        let (m, n) = self.r.dimension();
+
+        if m < n {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (n, n),
+                found: (m, n),
+            });
+        }
+
+        let c = self.q.transpose().try_mul_vector(b)?;
+        let top = Matrix::from_data(n, n, self.r.data[..n * n].to_vec());
+
+        Solver::new().try_backward_substitution(&top, &Vector::create_with_vec(c.components[..n].to_vec()))
+    }
+}
+
+impl Decomposer {
+    pub fn updatable_qr(&self, matrix: &Matrix) -> UpdatableQr {
+        let qr = self.householder(matrix);
+
+        UpdatableQr {
+            q: qr.get_q(),
+            r: qr.get_r(),
+        }
+    }
+
+    pub fn cholesky_update(&self, l: &mut Matrix, x: &Vector) {
+        self.try_cholesky_update(l, x).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_cholesky_update(&self, l: &mut Matrix, x: &Vector) -> Result<(), LinalgError> {
+        // L L^T + x x^T, in place
+        self.cholesky_rank_one(l, x, 1.0)
+    }
+
+    pub fn cholesky_downdate(&self, l: &mut Matrix, x: &Vector) {
+        self.try_cholesky_downdate(l, x).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_cholesky_downdate(&self, l: &mut Matrix, x: &Vector) -> Result<(), LinalgError> {
+        // L L^T - x x^T, fails if that is not positive definite and
+        // then leaves L as it was
+        self.cholesky_rank_one(l, x, -1.0)
+    }
+
+    fn cholesky_rank_one(&self, l: &mut Matrix, x: &Vector, sign: f64) -> Result<(), LinalgError> {
+        l.check_square()?;
+
+        let n = l.dimension().0;
+
+        if x.size() != n {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (1, n),
+                found: x.dimension(),
+            });
+        }
+
+        let mut a = l.data.clone();
+        let mut x = x.components.clone();
+
+        for k in 0..n {
+            let lkk = a[k * n + k];
+            let squared = lkk * lkk + sign * x[k] * x[k];
+
+            if squared <= 0.0 || lkk == 0.0 {
+                return Err(LinalgError::NotPositiveDefinite { pivot: k + 1 });
+            }
+
+            let r = squared.sqrt();
+            let (c, s) = (r / lkk, x[k] / lkk);
+            a[k * n + k] = r;
+
+            for i in (k + 1)..n {
+                a[i * n + k] = (a[i * n + k] + sign * s * x[i]) / c;
+                x[i] = c * x[i] - s * a[i * n + k];
+            }
+        }
+
+        l.data = a;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_matrix(m: usize, n: usize) -> Matrix {
+        let data = (0..m * n).map(|k| ((k * k * 7 + k * 3) as f64 * 0.37).sin()).collect();
+
+        Matrix::from_data(m, n, data)
+    }
+
+    fn max_difference(a: &Matrix, b: &Matrix) -> f64 {
+        a.data.iter().zip(b.data.iter()).map(|(x, y)| (x - y).abs()).fold(0.0, f64::max)
+    }
+
+    fn assert_factors(qr: &UpdatableQr, a: &Matrix) {
+        let (m, n) = a.dimension();
+
+        assert_eq!(qr.dimension(), (m, n));
+        assert!(max_difference(&(qr.q() * qr.r()), a) < 1e-13);
+        assert!(max_difference(&(&qr.q().transpose() * qr.q()), &Matrix::identity(m)) < 1e-13);
+
+        for i in 0..m {
+            for j in 0..min(i, n) {
+                assert_eq!(qr.r().data[i * n + j], 0.0);
+            }
+        }
+    }
+
+    fn rows_of(a: &Matrix) -> Vec<Vec<f64>> {
+        a.data.chunks_exact(a.columns).map(|row| row.to_vec()).collect()
+    }
+
+    fn from_rows(rows: &[Vec<f64>], n: usize) -> Matrix {
+        Matrix::from_data(rows.len(), n, rows.concat())
+    }
+
+    #[test]
+    fn givens_zeroes_second_component() {
+        let rotation = Givens::new(3.0, -4.0);
+        let (r, zero) = rotation.apply(3.0, -4.0);
+
+        assert!((r - 5.0).abs() < 1e-15 && zero.abs() < 1e-15);
+        assert_eq!(Givens::new(2.0, 0.0).apply(2.0, 0.0), (2.0, 0.0));
+    }
+
+    #[test]
+    fn insert_rows() {
+        for (m, n) in [(6, 3), (3, 5), (4, 4), (1, 2)] {
+            let a = test_matrix(m, n);
+            let row = Vector::create_with_vec((0..n).map(|j| j as f64 - 0.5).collect());
+
+            for position in 1..=(m + 1) {
+                let mut qr = Decomposer::new().updatable_qr(&a);
+                qr.insert_row(position, &row);
+
+                let mut rows = rows_of(&a);
+                rows.insert(position - 1, row.components.clone());
+
+                assert_factors(&qr, &from_rows(&rows, n));
+            }
+        }
+    }
+
+    #[test]
+    fn delete_rows() {
+        for (m, n) in [(6, 3), (3, 5), (4, 4), (2, 2)] {
+            let a = test_matrix(m, n);
+
+            for position in 1..=m {
+                let mut qr = Decomposer::new().updatable_qr(&a);
+                qr.delete_row(position);
+
+                let mut rows = rows_of(&a);
+                rows.remove(position - 1);
+
+                assert_factors(&qr, &from_rows(&rows, n));
+            }
+        }
+
+        let mut qr = Decomposer::new().updatable_qr(&test_matrix(1, 3));
+
+        assert_eq!(qr.try_delete_row(1), Err(LinalgError::Empty));
+        assert!(qr.try_delete_row(2).is_err());
+    }
+
+    #[test]
+    fn append_then_delete_restores_the_matrix() {
+        let a = test_matrix(5, 3);
+        let mut qr = Decomposer::new().updatable_qr(&a);
+
+        qr.append_row(&Vector::new(&[1.0, 2.0, 3.0]));
+        qr.delete_row(6);
+
+        assert_factors(&qr, &a);
+    }
+
+    #[test]
+    fn rank_one_updates() {
+        for (m, n) in [(6, 3), (3, 5), (4, 4)] {
+            let a = test_matrix(m, n);
+            let u = Vector::create_with_vec((0..m).map(|i| (i as f64).cos()).collect());
+            let v = Vector::create_with_vec((0..n).map(|j| 1.0 / (j as f64 + 1.0)).collect());
+            let mut qr = Decomposer::new().updatable_qr(&a);
+            qr.rank_one_update(&u, &v);
+
+            let mut updated = a.clone();
+
+            for i in 0..m {
+                for j in 0..n {
+                    updated.data[i * n + j] += u.components[i] * v.components[j];
+                }
+            }
+
+            assert_factors(&qr, &updated);
+        }
+    }
+
+    #[test]
+    fn cholesky_update_and_downdate() {
+        let g = test_matrix(5, 5);
+        let a = &(&g.transpose() * &g) + &Matrix::identity(5);
+        let x = Vector::new(&[0.5, -1.0, 2.0, 0.0, 1.5]);
+        let decomposer = Decomposer::new();
+        let mut l = decomposer.cholesky(&a);
+
+        decomposer.cholesky_update(&mut l, &x);
+
+        let mut updated = a.clone();
+
+        for i in 0..5 {
+            for j in 0..5 {
+                updated.data[i * 5 + j] += x.components[i] * x.components[j];
+            }
+        }
+
+        assert!(max_difference(&(&l * &l.transpose()), &updated) < 1e-12);
+
+        decomposer.cholesky_downdate(&mut l, &x);
+
+        assert!(max_difference(&l, &decomposer.cholesky(&a)) < 1e-12);
+    }
+
+    #[test]
+    fn failed_downdate_leaves_the_factor_unchanged() {
+        let decomposer = Decomposer::new();
+        let mut l = decomposer.cholesky(&Matrix::new(&[&[4.0, 2.0], &[2.0, 3.0]]));
+        let before = l.clone();
+
+        assert_eq!(decomposer.try_cholesky_downdate(&mut l, &Vector::new(&[1.0, 3.0])),
+                   Err(LinalgError::NotPositiveDefinite { pivot: 2 }));
+        assert_eq!(l, before);
+    }
+}
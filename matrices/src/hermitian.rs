@@ -0,0 +1,304 @@
+use crate::*;
+
+impl<T: Scalar> Matrix<T> {
+    pub fn conjugate(&self) -> Self {
+        let data = self.data.iter()
+                   .map(|x| x.conjugate())
+                   .collect::<Vec<T>>();
+
+        Self::from_data(self.rows, self.columns, data)
+    }
+
+    pub fn adjoint(&self) -> Self {
+        // conjugate transpose, equal to the transpose for real matrices
+        self.transpose().conjugate()
+    }
+
+    pub fn is_hermitian(&self, tolerance: f64) -> bool {
+        let (m, n) = self.dimension();
+
+        if m != n {
+            return false;
+        }
+
+        for i in 0..n {
+            for j in i..n {
+                let difference = self.data[i * n + j].clone()
+                                 - self.data[j * n + i].conjugate();
+
+                if difference.modulus() > tolerance {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    pub fn is_unitary(&self, tolerance: f64) -> bool {
+        // A^H A = I, entry by entry
+        let (m, n) = self.dimension();
+
+        if m != n {
+            return false;
+        }
+
+        let product = &self.adjoint() * self;
+        let identity = Matrix::<T>::identity(n);
+
+        product.data.iter()
+               .zip(identity.data.iter())
+               .all(|(a, b)| (a.clone() - b.clone()).modulus() <= tolerance)
+    }
+}
+
+impl Matrix<Complex> {
+    pub fn from_real(matrix: &Matrix) -> Self {
+        let data = matrix.data.iter()
+                   .map(|&x| Complex::new(x, 0.0))
+                   .collect::<Vec<Complex>>();
+
+        Self::from_data(matrix.rows, matrix.columns, data)
+    }
+
+    pub fn real_part(&self) -> Matrix {
+        let data = self.data.iter().map(|x| x.real()).collect::<Vec<f64>>();
+
+        Matrix::from_data(self.rows, self.columns, data)
+    }
+
+    pub fn imaginary_part(&self) -> Matrix {
+        let data = self.data.iter().map(|x| x.imaginary()).collect::<Vec<f64>>();
+
+        Matrix::from_data(self.rows, self.columns, data)
+    }
+}
+
+impl Decomposer {
+    pub fn complex_qr(&self, matrix: &Matrix<Complex>) -> (Matrix<Complex>, Matrix<Complex>) {
+        // A = Q R with Q unitary (m x m) and R upper triangular (m x n).
+        // each reflector is H = I - 2 v v^H / (v^H v) with
+        // v = x + e^(i arg x1) |x| e1, which avoids cancellation in v1
+        let (m, n) = matrix.dimension();
+        let mut r = matrix.clone();
+        let mut q = Matrix::<Complex>::identity(m);
+
+        for k in 0..min(m.saturating_sub(1), n) {
+            let mut v = (k..m).map(|i| r.data[i * n + k]).collect::<Vec<Complex>>();
+            let alpha = v.iter().map(|x| x.norm() * x.norm()).sum::<f64>().sqrt();
+
+            if alpha == 0.0 {
+                continue;
+            }
+
+            let phase = if v[0].norm() == 0.0 {
+                Complex::new(1.0, 0.0)
+            } else {
+                &v[0] * (1.0 / v[0].norm())
+            };
+
+            v[0] = v[0] + &phase * alpha;
+
+            let v_norm = v.iter().map(|x| x.norm() * x.norm()).sum::<f64>();
+
+            // R <- H R, only rows k..m change
+            for j in k..n {
+                let s = v.iter()
+                        .enumerate()
+                        .fold(Complex::new(0.0, 0.0),
+                              |s, (l, vl)| s + vl.conjugate() * r.data[(k + l) * n + j]);
+                let factor = &s * (2.0 / v_norm);
+
+                for (l, vl) in v.iter().enumerate() {
+                    r.data[(k + l) * n + j] = r.data[(k + l) * n + j] - factor * *vl;
+                }
+            }
+
+            for i in (k + 1)..m {
+                r.data[i * n + k] = Complex::new(0.0, 0.0);
+            }
+
+            // Q <- Q H, only columns k..m change
+            for i in 0..m {
+                let s = v.iter()
+                        .enumerate()
+                        .fold(Complex::new(0.0, 0.0),
+                              |s, (l, vl)| s + q.data[i * m + k + l] * *vl);
+                let factor = &s * (2.0 / v_norm);
+
+                for (l, vl) in v.iter().enumerate() {
+                    q.data[i * m + k + l] = q.data[i * m + k + l] - factor * vl.conjugate();
+                }
+            }
+        }
+
+        (q, r)
+    }
+}
+
+impl LeastSquares {
+    pub fn via_complex_qr(&self, matrix: &Matrix<Complex>, b: &Vector<Complex>) -> Vector<Complex> {
+        self.try_via_complex_qr(matrix, b).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_via_complex_qr
+    (
+        &self,
+        matrix: &Matrix<Complex>,
+        b: &Vector<Complex>
+    ) -> Result<Vector<Complex>, LinalgError>
+    {
+        // minimizes |A x - b| for full column rank A with m >= n
+        // by solving R1 x = (Q^H b)(1..n)
+        let (m, n) = matrix.dimension();
+
+        if m < n {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (n, n),
+                found: (m, n),
+            });
+        }
+
+        if b.size() != m {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (1, m),
+                found: b.dimension(),
+            });
+        }
+
+        let (q, r) = Decomposer::new().complex_qr(matrix);
+        let qhb = q.adjoint().try_mul_vector(b)?;
+        let r1 = r.try_sub_matrix((1, n), (1, n))?;
+        let c = Vector::create_with_vec(qhb.components[..n].to_vec());
+
+        Solver::new().try_backward_substitution(&r1, &c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(real: f64, imaginary: f64) -> Complex {
+        Complex::new(real, imaginary)
+    }
+
+    fn max_difference(a: &Matrix<Complex>, b: &Matrix<Complex>) -> f64 {
+        assert_eq!(a.dimension(), b.dimension());
+
+        a.data.iter()
+         .zip(b.data.iter())
+         .map(|(x, y)| (*x - *y).norm())
+         .fold(0.0, f64::max)
+    }
+
+    fn tall() -> Matrix<Complex> {
+        Matrix::new(&[&[c(1.0, 2.0), c(3.0, -1.0)],
+                      &[c(0.0, 4.0), c(5.0, 0.0)],
+                      &[c(2.0, 0.0), c(-1.0, 1.0)]])
+    }
+
+    #[test]
+    fn adjoint_conjugates_the_transpose() {
+        let a = tall();
+        let adjoint = a.adjoint();
+
+        assert_eq!(adjoint, Matrix::new(&[&[c(1.0, -2.0), c(0.0, -4.0), c(2.0, 0.0)],
+                                          &[c(3.0, 1.0), c(5.0, 0.0), c(-1.0, -1.0)]]));
+        assert_eq!(adjoint.adjoint(), a);
+        assert_eq!(a.conjugate().imaginary_part(), &a.imaginary_part() * -1.0);
+        assert_eq!(a.real_part(), Matrix::new(&[&[1.0, 3.0], &[0.0, 5.0], &[2.0, -1.0]]));
+
+        let real = Matrix::new(&[&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]]);
+
+        assert_eq!(real.adjoint(), real.transpose());
+        assert_eq!(Matrix::from_real(&real).adjoint(), Matrix::from_real(&real.transpose()));
+    }
+
+    #[test]
+    fn hermitian_and_unitary_matrices() {
+        let hermitian = Matrix::new(&[&[c(2.0, 0.0), c(1.0, -1.0)],
+                                      &[c(1.0, 1.0), c(3.0, 0.0)]]);
+        // symmetric but not hermitian
+        let symmetric = Matrix::new(&[&[c(2.0, 0.0), c(1.0, -1.0)],
+                                      &[c(1.0, -1.0), c(3.0, 0.0)]]);
+        // the diagonal of a hermitian matrix is real
+        let imaginary_diagonal = Matrix::new(&[&[c(1.0, 1.0)]]);
+        let perturbed = Matrix::new(&[&[c(2.0, 0.0), c(1.0, -1.0)],
+                                      &[c(1.0, 1.0 + 1e-10), c(3.0, 0.0)]]);
+
+        assert!(hermitian.is_hermitian(0.0));
+        assert!(!symmetric.is_hermitian(1e-12));
+        assert!(!imaginary_diagonal.is_hermitian(1e-12));
+        assert!(!perturbed.is_hermitian(0.0));
+        assert!(perturbed.is_hermitian(1e-9));
+        assert!(!tall().is_hermitian(1e-12));
+
+        let s = 1.0 / 2.0_f64.sqrt();
+        let unitary = Matrix::new(&[&[c(s, 0.0), c(0.0, s)],
+                                    &[c(0.0, s), c(s, 0.0)]]);
+        // unitary for the transpose, not for the adjoint
+        let complex_rotation = Matrix::new(&[&[c(s, 0.0), c(0.0, s)],
+                                             &[c(0.0, -s), c(s, 0.0)]]);
+
+        assert!(unitary.is_unitary(1e-15));
+        assert!(!complex_rotation.is_unitary(1e-12));
+        assert!(!hermitian.is_unitary(1e-12));
+        assert!(!tall().is_unitary(1e-12));
+    }
+
+    #[test]
+    fn complex_qr_factors_complex_matrices() {
+        let decomposer = Decomposer::new();
+        // the first column of the square matrix starts with a zero
+        let square = Matrix::new(&[&[c(0.0, 0.0), c(1.0, 1.0), c(2.0, -3.0)],
+                                   &[c(0.0, 1.0), c(-2.0, 0.0), c(1.0, 1.0)],
+                                   &[c(1.0, 0.0), c(0.0, 2.0), c(4.0, 0.5)]]);
+
+        for a in [tall(), square, tall().adjoint()] {
+            let (m, n) = a.dimension();
+            let (q, r) = decomposer.complex_qr(&a);
+
+            assert_eq!(q.dimension(), (m, m));
+            assert_eq!(r.dimension(), (m, n));
+            assert!(q.is_unitary(1e-14));
+            assert!(max_difference(&(&q * &r), &a) < 1e-14);
+
+            for i in 1..=m {
+                for j in 1..min(i, n + 1) {
+                    assert_eq!(r.element(i, j), Some(c(0.0, 0.0)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn complex_least_squares() {
+        let least_squares = LeastSquares::default();
+        let a = tall();
+        let x = Vector::new(&[c(1.0, -1.0), c(2.0, 3.0)]);
+        let b = a.try_mul_vector(&x).unwrap();
+        let solution = least_squares.via_complex_qr(&a, &b);
+
+        for (s, x) in solution.components.iter().zip(x.components.iter()) {
+            assert!((*s - *x).norm() < 1e-14);
+        }
+
+        // an inconsistent right hand side gets a residual orthogonal to
+        // the columns of A
+        let b = Vector::new(&[c(1.0, 0.0), c(0.0, 1.0), c(1.0, 1.0)]);
+        let solution = least_squares.via_complex_qr(&a, &b);
+        let residual = Vector::create_with_vec(
+            a.try_mul_vector(&solution).unwrap().components.iter()
+             .zip(b.components.iter())
+             .map(|(ax, b)| *ax - *b)
+             .collect());
+        let normal = a.adjoint().try_mul_vector(&residual).unwrap();
+
+        assert!(normal.components.iter().all(|z| z.norm() < 1e-14));
+
+        assert!(least_squares.try_via_complex_qr(&a.adjoint(), &Vector::new(&[c(1.0, 0.0); 2])).is_err());
+        assert_eq!(least_squares.try_via_complex_qr(&a, &x),
+                   Err(LinalgError::DimensionMismatch { expected: (1, 3), found: (1, 2) }));
+    }
+}
@@ -0,0 +1,541 @@
+use crate::*;
+
+// compact householder qr of an m x n matrix, A = Q R with
+// Q = H_1 H_2 ... H_p, p = min(m, n) and H_k = I - tau_k v_k v_k^T.
+// R is stored on and above the diagonal of `qr`, and below the diagonal
+// column k holds v_k, whose first component is an implicit 1
+pub struct Houlseholder {
+    qr: Matrix,
+    tau: Vec<f64>,
+}
+
+impl Houlseholder {
+    fn reflectors(&self) -> usize {
+        self.tau.len()
+    }
+
+    pub fn dimension(&self) -> (usize, usize) {
+        self.qr.dimension()
+    }
+
+    // y = H_k y for the rows k..m of column `column` of a row-major
+    // buffer with `columns` columns
+    fn reflect(&self, k: usize, y: &mut [f64], columns: usize, column: usize) {
+        let tau = self.tau[k];
+
+        if tau == 0.0 {
+            return;
+        }
+
+        let (m, n) = self.qr.dimension();
+        let v = |i: usize| if i == k { 1.0 } else { self.qr.data[i * n + k] };
+
+        let w = (k..m).map(|i| v(i) * y[i * columns + column]).sum::<f64>();
+
+        for i in k..m {
+            y[i * columns + column] -= tau * v(i) * w;
+        }
+    }
+
+    pub fn apply_q(&self, b: &Vector) -> Vector {
+        self.try_apply_q(b).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_apply_q(&self, b: &Vector) -> Result<Vector, LinalgError> {
+        let mut result = self.vector_as_column(b)?;
+        self.try_apply_q_matrix_in_place(&mut result, false)?;
+
+        Ok(Vector::create_with_vec(result.data))
+    }
+
+    pub fn apply_qt(&self, b: &Vector) -> Vector {
+        self.try_apply_qt(b).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_apply_qt(&self, b: &Vector) -> Result<Vector, LinalgError> {
+        let mut result = self.vector_as_column(b)?;
+        self.try_apply_q_matrix_in_place(&mut result, true)?;
+
+        Ok(Vector::create_with_vec(result.data))
+    }
+
+    pub fn apply_q_matrix(&self, b: &Matrix) -> Matrix {
+        self.try_apply_q_matrix(b).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_apply_q_matrix(&self, b: &Matrix) -> Result<Matrix, LinalgError> {
+        let mut result = b.clone();
+        self.try_apply_q_matrix_in_place(&mut result, false)?;
+
+        Ok(result)
+    }
+
+    pub fn apply_qt_matrix(&self, b: &Matrix) -> Matrix {
+        self.try_apply_qt_matrix(b).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_apply_qt_matrix(&self, b: &Matrix) -> Result<Matrix, LinalgError> {
+        let mut result = b.clone();
+        self.try_apply_q_matrix_in_place(&mut result, true)?;
+
+        Ok(result)
+    }
+
+    fn vector_as_column(&self, b: &Vector) -> Result<Matrix, LinalgError> {
+        let m = self.qr.dimension().0;
+
+        if b.size() != m {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (1, m),
+                found: b.dimension(),
+            });
+        }
+
+        Ok(Matrix::from_data(m, 1, b.components.clone()))
+    }
+
+    fn try_apply_q_matrix_in_place(&self, b: &mut Matrix, transpose: bool) -> Result<(), LinalgError> {
+        // Q^T B = H_p ... H_1 B and Q B = H_1 ... H_p B
+        let m = self.qr.dimension().0;
+        let (rows, columns) = b.dimension();
+
+        if rows != m {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (m, columns),
+                found: (rows, columns),
+            });
+        }
+
+        let p = self.reflectors();
+
+        for step in 0..p {
+            let k = if transpose { step } else { p - 1 - step };
+
+            for j in 0..columns {
+                self.reflect(k, &mut b.data, columns, j);
+            }
+        }
+
+        Ok(())
+    }
+
+    // m x m
+    pub fn get_q(&self) -> Matrix {
+        let m = self.qr.dimension().0;
+
+        self.apply_q_matrix(&Matrix::identity(m))
+    }
+
+    // the first min(m, n) columns of Q, m x min(m, n)
+    pub fn get_thin_q(&self) -> Matrix {
+        let m = self.qr.dimension().0;
+        let p = self.reflectors();
+        let mut identity = Matrix::zero(m, p);
+
+        for i in 0..p {
+            identity.data[i * p + i] = 1.0;
+        }
+
+        self.apply_q_matrix(&identity)
+    }
+
+    // m x n
+    pub fn get_r(&self) -> Matrix {
+        let (m, n) = self.qr.dimension();
+        let mut r = Matrix::zero(m, n);
+
+        for i in 0..min(m, n) {
+            r.data[i * n + i..(i + 1) * n].copy_from_slice(&self.qr.data[i * n + i..(i + 1) * n]);
+        }
+
+        r
+    }
+
+    // the first min(m, n) rows of R, min(m, n) x n
+    pub fn get_thin_r(&self) -> Matrix {
+        let n = self.qr.dimension().1;
+        let p = self.reflectors();
+        let r = self.get_r();
+
+        Matrix::from_data(p, n, r.data[..p * n].to_vec())
+    }
+}
+
+// householder qr of the matrix, also choosing at each step the remaining
+// column of largest norm when `pivoting`. returns the compact factors and
+// the column permutation
+fn factor(matrix: &Matrix, pivoting: bool) -> (Matrix, Vec<f64>, Vec<usize>) {
+    // column k is reduced with the reflector that maps the part of it
+    // on and below the diagonal to beta e_1, |beta| = |x|, with the
+    // sign of beta opposite to x_1 to avoid cancellation
+    let (m, n) = matrix.dimension();
+    let mut qr = matrix.clone();
+    let a = &mut qr.data;
+    let p = min(m, n);
+    let mut tau = vec![0.0; p];
+    let mut permutation = (1..=n).collect::<Vec<usize>>();
+
+    // norms of the columns below the current row, updated after each step
+    // and recomputed when the update loses too many digits
+    let column_norm = |a: &[f64], j: usize, from: usize| {
+        (from..m).map(|i| a[i * n + j].powi(2)).sum::<f64>().sqrt()
+    };
+    let mut norms = (0..n).map(|j| column_norm(a, j, 0)).collect::<Vec<f64>>();
+    let mut reference = norms.clone();
+
+    for k in 0..p {
+        if pivoting {
+            let best = (k..n).fold(k, |best, j| if norms[j] > norms[best] { j } else { best });
+
+            if best != k {
+                for row in a.chunks_exact_mut(n) {
+                    row.swap(k, best);
+                }
+
+                norms.swap(k, best);
+                reference.swap(k, best);
+                permutation.swap(k, best);
+            }
+        }
+
+        let alpha = a[k * n + k];
+        let tail_norm = column_norm(a, k, k + 1);
+
+        if tail_norm != 0.0 {
+            let beta = -alpha.signum() * alpha.hypot(tail_norm);
+            tau[k] = (beta - alpha) / beta;
+
+            let scale = 1.0 / (alpha - beta);
+
+            for i in (k + 1)..m {
+                a[i * n + k] *= scale;
+            }
+
+            a[k * n + k] = beta;
+
+            // H_k applied to the columns on the right
+            for j in (k + 1)..n {
+                let w = a[k * n + j] + ((k + 1)..m).map(|i| a[i * n + k] * a[i * n + j]).sum::<f64>();
+                let tw = tau[k] * w;
+
+                a[k * n + j] -= tw;
+
+                for i in (k + 1)..m {
+                    a[i * n + j] -= tw * a[i * n + k];
+                }
+            }
+        }
+
+        if pivoting {
+            for j in (k + 1)..n {
+                if norms[j] == 0.0 {
+                    continue;
+                }
+
+                let ratio = a[k * n + j].abs() / norms[j];
+                let updated = norms[j] * (1.0 - ratio * ratio).max(0.0).sqrt();
+
+                if updated <= f64::EPSILON.sqrt() * reference[j] {
+                    norms[j] = column_norm(a, j, k + 1);
+                    reference[j] = norms[j];
+                } else {
+                    norms[j] = updated;
+                }
+            }
+        }
+    }
+
+    (qr, tau, permutation)
+}
+
+// householder qr with column pivoting, A P = Q R. the diagonal of R is
+// non increasing in absolute value, and the numerical rank is the number
+// of its entries above tolerance * |R(1, 1)|
+pub struct PivotedQr {
+    qr: Houlseholder,
+    permutation: Vec<usize>,
+    rank: usize,
+}
+
+impl PivotedQr {
+    pub fn qr(&self) -> &Houlseholder {
+        &self.qr
+    }
+
+    // column j of A P is column permutation[j - 1] of A
+    pub fn permutation(&self) -> &[usize] {
+        &self.permutation
+    }
+
+    pub fn p(&self) -> Matrix {
+        let n = self.permutation.len();
+        let mut p = Matrix::zero(n, n);
+
+        for (j, &column) in self.permutation.iter().enumerate() {
+            p.data[(column - 1) * n + j] = 1.0;
+        }
+
+        p
+    }
+
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    // columns of A that span its column space, in pivot order
+    pub fn independent_columns(&self) -> Vec<usize> {
+        self.permutation[..self.rank].to_vec()
+    }
+
+    pub fn solve_least_squares(&self, b: &Vector) -> Vector {
+        self.try_solve_least_squares(b).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_solve_least_squares(&self, b: &Vector) -> Result<Vector, LinalgError> {
+        // basic solution: minimizes |A x - b| with only the `rank`
+        // independent columns, x = P [R11^-1 (Q^T b)_1; 0]. for full
+        // column rank it is the least squares solution
+        let c = self.qr.try_apply_qt(b)?;
+        let n = self.qr.qr.dimension().1;
+        let r = self.rank;
+        let data = &self.qr.qr.data;
+        let mut z = vec![0.0; r];
+
+        for i in (0..r).rev() {
+            let sum = ((i + 1)..r).map(|j| data[i * n + j] * z[j]).sum::<f64>();
+            z[i] = (c.components[i] - sum) / data[i * n + i];
+        }
+
+        let mut x = vec![0.0; n];
+
+        for (k, zk) in z.into_iter().enumerate() {
+            x[self.permutation[k] - 1] = zk;
+        }
+
+        Ok(Vector::create_with_vec(x))
+    }
+}
+
+impl Decomposer {
+    pub fn householder(&self, matrix: &Matrix) -> Houlseholder {
+        let (qr, tau, _) = factor(matrix, false);
+
+        Houlseholder {
+            qr,
+            tau,
+        }
+    }
+
+    pub fn pivoted_qr(&self, matrix: &Matrix, tolerance: f64) -> PivotedQr {
+        self.try_pivoted_qr(matrix, tolerance).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_pivoted_qr(&self, matrix: &Matrix, tolerance: f64) -> Result<PivotedQr, LinalgError> {
+        // the tolerance is relative to |R(1, 1)|
+        if tolerance < 0.0 || tolerance.is_nan() {
+            return Err(LinalgError::InvalidParameter {
+                parameter: "tolerance",
+                requirement: "non negative",
+            });
+        }
+
+        let (qr, tau, permutation) = factor(matrix, true);
+        let (m, n) = qr.dimension();
+        let diagonal = (0..min(m, n)).map(|i| qr.data[i * n + i].abs()).collect::<Vec<f64>>();
+        let largest = diagonal.first().copied().unwrap_or(0.0);
+        let rank = diagonal.iter().take_while(|&&d| d > tolerance * largest && d > 0.0).count();
+
+        Ok(PivotedQr {
+            qr: Houlseholder {
+                qr,
+                tau,
+            },
+            permutation,
+            rank,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn max_difference(a: &Matrix, b: &Matrix) -> f64 {
+        assert_eq!(a.dimension(), b.dimension());
+        a.data.iter().zip(b.data.iter()).map(|(x, y)| (x - y).abs()).fold(0.0, f64::max)
+    }
+
+    // Q = H_1 ... H_p multiplied out from the stored reflectors
+    fn explicit_q(qr: &Houlseholder) -> Matrix {
+        let (m, n) = qr.dimension();
+        let mut q = Matrix::identity(m);
+
+        for k in 0..qr.reflectors() {
+            let mut v = vec![0.0; m];
+            v[k] = 1.0;
+
+            for i in (k + 1)..m {
+                v[i] = qr.qr.data[i * n + k];
+            }
+
+            let mut h = Matrix::identity(m);
+
+            for i in 0..m {
+                for j in 0..m {
+                    h.data[i * m + j] -= qr.tau[k] * v[i] * v[j];
+                }
+            }
+
+            q = &q * &h;
+        }
+
+        q
+    }
+
+    fn samples() -> Vec<Matrix> {
+        vec![
+            Matrix::new(&[&[2.0, -1.0, 0.5], &[1.0, 3.0, -2.0], &[0.0, 1.0, 4.0], &[-1.0, 2.0, 1.0]]),
+            Matrix::new(&[&[1.0, 2.0, 3.0, 4.0], &[2.0, -1.0, 0.0, 1.0]]),
+            Matrix::new(&[&[4.0, 1.0], &[1.0, 3.0]]),
+            Matrix::new(&[&[1.0, 2.0], &[2.0, 4.0], &[3.0, 6.0]]),
+            Matrix::new(&[&[3.0], &[0.0], &[4.0]]),
+        ]
+    }
+
+    #[test]
+    fn q_and_r_rebuild_the_matrix() {
+        for a in samples() {
+            let (m, n) = a.dimension();
+            let p = m.min(n);
+            let qr = Decomposer::new().householder(&a);
+            let q = qr.get_q();
+            let r = qr.get_r();
+
+            assert!(max_difference(&q, &explicit_q(&qr)) < 1e-14);
+            assert!(max_difference(&(&q.transpose() * &q), &Matrix::identity(m)) < 1e-14);
+            assert!(max_difference(&(&q * &r), &a) < 1e-13);
+
+            let (thin_q, thin_r) = (qr.get_thin_q(), qr.get_thin_r());
+
+            assert_eq!(thin_q.dimension(), (m, p));
+            assert_eq!(thin_r.dimension(), (p, n));
+            assert!(max_difference(&(&thin_q.transpose() * &thin_q), &Matrix::identity(p)) < 1e-14);
+            assert!(max_difference(&(&thin_q * &thin_r), &a) < 1e-13);
+
+            for i in 1..=m {
+                for j in 1..i.min(n + 1) {
+                    assert_eq!(r.element(i, j).unwrap(), 0.0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn applying_q_matches_the_explicit_matrix() {
+        for a in samples() {
+            let m = a.dimension().0;
+            let qr = Decomposer::new().householder(&a);
+            let q = explicit_q(&qr);
+            let b = Vector::create_with_vec((1..=m).map(|i| (i * i) as f64 - 2.0).collect());
+
+            assert!((&qr.apply_q(&b) - &(&q * &b)).components.iter().all(|d| d.abs() < 1e-13));
+            assert!((&qr.apply_qt(&b) - &(&q.transpose() * &b)).components.iter().all(|d| d.abs() < 1e-13));
+            assert!((&qr.apply_qt(&qr.apply_q(&b)) - &b).components.iter().all(|d| d.abs() < 1e-14));
+
+            let block = Matrix::create_with_vectors(&vec![b.clone(); m]).transpose();
+
+            assert!(max_difference(&qr.apply_q_matrix(&block), &(&q * &block)) < 1e-13);
+            assert!(max_difference(&qr.apply_qt_matrix(&block), &(&q.transpose() * &block)) < 1e-13);
+        }
+    }
+
+    #[test]
+    fn wide_matrices_use_min_dimension_reflectors() {
+        let a = Matrix::new(&[&[0.0, 1.0, 2.0, 3.0, 4.0], &[5.0, 6.0, 7.0, 8.0, 9.0]]);
+        let qr = Decomposer::new().householder(&a);
+
+        assert_eq!(qr.reflectors(), 2);
+        assert_eq!(qr.get_q().dimension(), (2, 2));
+        assert!(max_difference(&(&qr.get_q() * &qr.get_r()), &a) < 1e-13);
+    }
+
+    // rank 2: column 3 = column 1 + column 2 and column 4 = 2 column 2
+    fn rank_deficient() -> Matrix {
+        Matrix::new(&[&[1.0, 0.0, 1.0, 0.0],
+                      &[2.0, 1.0, 3.0, 2.0],
+                      &[0.0, 3.0, 3.0, 6.0],
+                      &[1.0, -1.0, 0.0, -2.0],
+                      &[1.0, 2.0, 3.0, 4.0]])
+    }
+
+    #[test]
+    fn pivoted_qr_reveals_the_rank() {
+        let a = rank_deficient();
+        let pivoted = Decomposer::new().pivoted_qr(&a, 1e-12);
+        let qr = pivoted.qr();
+        let r = qr.get_r();
+
+        assert_eq!(pivoted.rank(), 2);
+        assert!(max_difference(&(&qr.get_q() * &r), &(&a * &pivoted.p())) < 1e-13);
+
+        // the largest column goes first and the diagonal of R doesn't increase
+        assert_eq!(pivoted.permutation()[0], 4);
+        assert!((1..4).all(|i| r.element(i, i).unwrap().abs() >= r.element(i + 1, i + 1).unwrap().abs()));
+        assert!(r.element(3, 3).unwrap().abs() < 1e-12);
+
+        let mut sorted = pivoted.permutation().to_vec();
+        sorted.sort();
+
+        assert_eq!(sorted, vec![1, 2, 3, 4]);
+        assert_eq!(pivoted.independent_columns(), pivoted.permutation()[..2].to_vec());
+
+        let full = Decomposer::new().pivoted_qr(&samples()[0], 1e-12);
+
+        assert_eq!(full.rank(), 3);
+        assert_eq!(Decomposer::new().pivoted_qr(&Matrix::zero(3, 2), 1e-12).rank(), 0);
+    }
+
+    #[test]
+    fn basic_solution_of_a_rank_deficient_system() {
+        let a = rank_deficient();
+        let x = Vector::new(&[1.0, -1.0, 2.0, 0.5]);
+        let b = &a * &x;
+        let pivoted = Decomposer::new().pivoted_qr(&a, 1e-12);
+        let basic = pivoted.solve_least_squares(&b);
+
+        // n - rank entries are zero, the others belong to the independent columns
+        assert_eq!(basic.components.iter().filter(|&&xi| xi == 0.0).count(), 4 - pivoted.rank());
+
+        for j in pivoted.permutation()[2..].iter() {
+            assert_eq!(basic.components[j - 1], 0.0);
+        }
+
+        assert!((&(&a * &basic) - &b).components.iter().all(|d| d.abs() < 1e-12));
+
+        // full column rank: the least squares solution
+        let a = samples()[0].clone();
+        let b = Vector::new(&[1.0, 2.0, 3.0, 4.0]);
+        let x = Decomposer::new().pivoted_qr(&a, 1e-12).solve_least_squares(&b);
+        let normal = &(&(&a.transpose() * &a).inverse() * &a.transpose()) * &b;
+
+        assert!((&x - &normal).components.iter().all(|d| d.abs() < 1e-12));
+    }
+
+    #[test]
+    fn pivoted_qr_tolerance_must_be_non_negative() {
+        for tolerance in [-1e-10, f64::NAN] {
+            assert!(matches!(Decomposer::new().try_pivoted_qr(&rank_deficient(), tolerance),
+                             Err(LinalgError::InvalidParameter { parameter: "tolerance", .. })));
+        }
+    }
+
+    #[test]
+    fn operands_with_the_wrong_size_are_rejected() {
+        let qr = Decomposer::new().householder(&samples()[0]);
+
+        assert_eq!(qr.try_apply_q(&Vector::new(&[1.0, 2.0])),
+                   Err(LinalgError::DimensionMismatch { expected: (1, 4), found: (1, 2) }));
+        assert_eq!(qr.try_apply_qt_matrix(&Matrix::identity(3)),
+                   Err(LinalgError::DimensionMismatch { expected: (4, 3), found: (3, 3) }));
+    }
+}
@@ -0,0 +1,505 @@
+use crate::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StopReason {
+    Converged,
+    MaxIterations,
+    // the method can't go on, e.g. p^T A p <= 0 in cg or a
+    // restart cycle of gmres that doesn't reduce the residual
+    Breakdown,
+    // the residual stopped being finite
+    Diverged,
+}
+
+pub enum StoppingCriterion {
+    // |b - A x| <= tolerance * |b|
+    RelativeResidual(f64),
+    // sum |x_new - x| < tolerance
+    AbsoluteStep(f64),
+    // called with (iteration, residual norm, step norm), the step
+    // norm is infinite before the first iteration
+    Custom(Box<dyn Fn(usize, f64, f64) -> bool>),
+}
+
+impl StoppingCriterion {
+    pub(crate) fn is_met
+    (
+        &self,
+        iteration: usize,
+        residual_norm: f64,
+        b_norm: f64,
+        step_norm: f64,
+    ) -> bool
+    {
+        match self {
+            StoppingCriterion::RelativeResidual(tolerance) => residual_norm <= tolerance * b_norm,
+            StoppingCriterion::AbsoluteStep(tolerance) => step_norm < *tolerance,
+            StoppingCriterion::Custom(function) => function(iteration, residual_norm, step_norm),
+        }
+    }
+}
+
+// outcome of an iterative solve. the solution is the last iterate,
+// also when the method did not converge
+#[derive(Debug, Clone)]
+pub struct IterativeResult {
+    solution: Vector,
+    iterations: usize,
+    // |b - A x| of the initial guess followed by one entry per iteration
+    residual_history: Vec<f64>,
+    stop_reason: StopReason,
+    // only known by the stationary methods, for operators with row access
+    diagonally_dominant: Option<bool>,
+}
+
+impl IterativeResult {
+    pub(crate) fn new
+    (
+        solution: Vector,
+        iterations: usize,
+        residual_history: Vec<f64>,
+        stop_reason: StopReason,
+    ) -> Self
+    {
+        Self {
+            solution,
+            iterations,
+            residual_history,
+            stop_reason,
+            diagonally_dominant: None,
+        }
+    }
+
+    pub(crate) fn with_diagonal_dominance(mut self, diagonally_dominant: Option<bool>) -> Self {
+        self.diagonally_dominant = diagonally_dominant;
+        self
+    }
+
+    pub fn solution(&self) -> Vector {
+        self.solution.clone()
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    pub fn residual_norm(&self) -> f64 {
+        self.residual_history[self.residual_history.len() - 1]
+    }
+
+    pub fn residual_history(&self) -> &[f64] {
+        &self.residual_history
+    }
+
+    pub fn stop_reason(&self) -> StopReason {
+        self.stop_reason
+    }
+
+    pub fn converged(&self) -> bool {
+        self.stop_reason == StopReason::Converged
+    }
+
+    pub fn diagonally_dominant(&self) -> Option<bool> {
+        self.diagonally_dominant
+    }
+}
+
+impl Solver {
+    pub fn conjugate_gradient<A: LinearOperator>
+    (
+        &self,
+        operator: &A,
+        b: &Vector,
+        error: f64,
+        max_iter: usize,
+    ) -> IterativeResult
+    {
+        self.try_conjugate_gradient(operator, b, error, max_iter)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_conjugate_gradient<A: LinearOperator>
+    (
+        &self,
+        operator: &A,
+        b: &Vector,
+        error: f64,
+        max_iter: usize,
+    ) -> Result<IterativeResult, LinalgError>
+    {
+        // only for symmetric positive definite matrices. stops when
+        // |b - A x| <= error * |b|, and also if p^T A p <= 0 shows
+        // that the matrix is not positive definite
+        self.check_operator_system(operator, b)?;
+
+        let n = b.size();
+        let tolerance = error * b.magnitude();
+
+        let mut x = Vector::zero(n);
+        let mut r = b.clone();
+        let mut p = r.clone();
+        let mut rr = r.dot_product(&r);
+        let mut history = vec![rr.sqrt()];
+
+        if rr.sqrt() <= tolerance {
+            return Ok(IterativeResult::new(x, 0, history, StopReason::Converged));
+        }
+
+        for k in 1..=max_iter {
+            let ap = operator.try_apply(&p)?;
+            let pap = p.dot_product(&ap);
+
+            if pap <= 0.0 {
+                return Ok(IterativeResult::new(x, k - 1, history, StopReason::Breakdown));
+            }
+
+            let alpha = rr / pap;
+            x = &x + &(&p * alpha);
+            r = &r - &(&ap * alpha);
+
+            let rr_new = r.dot_product(&r);
+            history.push(rr_new.sqrt());
+
+            if rr_new.sqrt() <= tolerance {
+                return Ok(IterativeResult::new(x, k, history, StopReason::Converged));
+            }
+
+            p = &r + &(&p * (rr_new / rr));
+            rr = rr_new;
+        }
+
+        Ok(IterativeResult::new(x, max_iter, history, StopReason::MaxIterations))
+    }
+
+    pub fn gmres<A: LinearOperator>
+    (
+        &self,
+        operator: &A,
+        b: &Vector,
+        error: f64,
+        max_iter: usize,
+        restart: usize,
+    ) -> IterativeResult
+    {
+        self.try_gmres(operator, b, error, max_iter, restart)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_gmres<A: LinearOperator>
+    (
+        &self,
+        operator: &A,
+        b: &Vector,
+        error: f64,
+        max_iter: usize,
+        restart: usize,
+    ) -> Result<IterativeResult, LinalgError>
+    {
+        // restarted gmres: builds an arnoldi basis of at most `restart`
+        // vectors and minimizes the residual over it with givens rotations.
+        // max_iter counts matrix vector products over all cycles
+        self.check_operator_system(operator, b)?;
+
+        let n = b.size();
+        let restart = restart.clamp(1, n);
+        let tolerance = error * b.magnitude();
+
+        let mut x = Vector::zero(n);
+        let mut r = b.clone();
+        let mut beta = r.magnitude();
+        let mut iterations = 0;
+        let mut history = vec![beta];
+        let mut stagnated = false;
+
+        while beta > tolerance && iterations < max_iter {
+            let mut basis = vec![&r * (1.0 / beta)];
+            let mut h = vec![vec![0.0; restart]; restart + 1];
+            let mut cs = vec![0.0; restart];
+            let mut sn = vec![0.0; restart];
+            let mut g = vec![0.0; restart + 1];
+            g[0] = beta;
+
+            let mut k = 0;
+            let cycle_start = history.len();
+
+            while k < restart && iterations < max_iter {
+                iterations += 1;
+
+                // modified gram schmidt against the current basis
+                let mut w = operator.try_apply(&basis[k])?;
+
+                for (i, v) in basis.iter().enumerate() {
+                    h[i][k] = v.dot_product(&w);
+                    w = &w - &(v * h[i][k]);
+                }
+
+                let h_next = w.magnitude();
+                h[k + 1][k] = h_next;
+
+                for i in 0..k {
+                    let temp = cs[i] * h[i][k] + sn[i] * h[i + 1][k];
+                    h[i + 1][k] = -sn[i] * h[i][k] + cs[i] * h[i + 1][k];
+                    h[i][k] = temp;
+                }
+
+                let rho = h[k][k].hypot(h[k + 1][k]);
+
+                if rho == 0.0 {
+                    // the residual can't be reduced any further in this cycle
+                    history.push(g[k].abs());
+                    break;
+                }
+
+                cs[k] = h[k][k] / rho;
+                sn[k] = h[k + 1][k] / rho;
+                h[k][k] = rho;
+                h[k + 1][k] = 0.0;
+                g[k + 1] = -sn[k] * g[k];
+                g[k] *= cs[k];
+
+                k += 1;
+                history.push(g[k].abs());
+
+                // a zero h_next means the krylov space is invariant,
+                // so the solution over it is exact
+                if g[k].abs() <= tolerance || h_next == 0.0 {
+                    break;
+                }
+
+                basis.push(&w * (1.0 / h_next));
+            }
+
+            // H(1..k, 1..k) y = g(1..k)
+            let mut y = vec![0.0; k];
+
+            for i in (0..k).rev() {
+                let sum = ((i + 1)..k).map(|j| h[i][j] * y[j]).sum::<f64>();
+                y[i] = (g[i] - sum) / h[i][i];
+            }
+
+            for (yi, v) in y.iter().zip(basis.iter()) {
+                x = &x + &(v * *yi);
+            }
+
+            r = b - &operator.try_apply(&x)?;
+            let new_beta = r.magnitude();
+
+            // the last estimate from the rotations is replaced by the true residual,
+            // never touching the entries of previous cycles
+            if history.len() > cycle_start {
+                let last = history.len() - 1;
+                history[last] = new_beta;
+            } else {
+                history.push(new_beta);
+            }
+
+            if k == 0 || new_beta >= beta && new_beta > tolerance {
+                // no progress in a whole cycle, restarting won't help
+                beta = new_beta;
+                stagnated = true;
+                break;
+            }
+
+            beta = new_beta;
+        }
+
+        let stop_reason = if beta <= tolerance {
+            StopReason::Converged
+        } else if stagnated {
+            StopReason::Breakdown
+        } else {
+            StopReason::MaxIterations
+        };
+
+        Ok(IterativeResult::new(x, iterations, history, stop_reason))
+    }
+
+    pub fn bicgstab<A: LinearOperator>
+    (
+        &self,
+        operator: &A,
+        b: &Vector,
+        error: f64,
+        max_iter: usize,
+    ) -> IterativeResult
+    {
+        self.try_bicgstab(operator, b, error, max_iter)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_bicgstab<A: LinearOperator>
+    (
+        &self,
+        operator: &A,
+        b: &Vector,
+        error: f64,
+        max_iter: usize,
+    ) -> Result<IterativeResult, LinalgError>
+    {
+        // stops when |b - A x| <= error * |b| or on a breakdown
+        // (rho = 0, r_hat . v = 0 or omega = 0), which is reported as not converged
+        self.check_operator_system(operator, b)?;
+
+        let n = b.size();
+        let tolerance = error * b.magnitude();
+
+        let mut x = Vector::zero(n);
+        let mut r = b.clone();
+        let r_hat = r.clone();
+        let mut p = Vector::zero(n);
+        let mut v = Vector::zero(n);
+        let (mut rho, mut alpha, mut omega) = (1.0, 1.0, 1.0);
+        let mut residual = r.magnitude();
+        let mut history = vec![residual];
+
+        if residual <= tolerance {
+            return Ok(IterativeResult::new(x, 0, history, StopReason::Converged));
+        }
+
+        for k in 1..=max_iter {
+            let rho_new = r_hat.dot_product(&r);
+
+            if rho_new == 0.0 {
+                return Ok(IterativeResult::new(x, k - 1, history, StopReason::Breakdown));
+            }
+
+            let beta = (rho_new / rho) * (alpha / omega);
+            p = &r + &(&(&p - &(&v * omega)) * beta);
+            v = operator.try_apply(&p)?;
+
+            let r_hat_v = r_hat.dot_product(&v);
+
+            if r_hat_v == 0.0 {
+                return Ok(IterativeResult::new(x, k - 1, history, StopReason::Breakdown));
+            }
+
+            alpha = rho_new / r_hat_v;
+
+            let s = &r - &(&v * alpha);
+
+            if s.magnitude() <= tolerance {
+                x = &x + &(&p * alpha);
+                history.push(s.magnitude());
+                return Ok(IterativeResult::new(x, k, history, StopReason::Converged));
+            }
+
+            let t = operator.try_apply(&s)?;
+            let tt = t.dot_product(&t);
+            omega = if tt == 0.0 { 0.0 } else { t.dot_product(&s) / tt };
+
+            x = &(&x + &(&p * alpha)) + &(&s * omega);
+            r = &s - &(&t * omega);
+            residual = r.magnitude();
+            history.push(residual);
+
+            if residual <= tolerance {
+                return Ok(IterativeResult::new(x, k, history, StopReason::Converged));
+            }
+
+            if omega == 0.0 {
+                return Ok(IterativeResult::new(x, k, history, StopReason::Breakdown));
+            }
+
+            rho = rho_new;
+        }
+
+        Ok(IterativeResult::new(x, max_iter, history, StopReason::MaxIterations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bicgstab_breakdown_on_orthogonal_search_direction() {
+        // r_hat = b and v = A b are orthogonal for a skew symmetric A
+        let a = Matrix::new(&[&[0.0, 1.0], &[-1.0, 0.0]]);
+        let b = Vector::new(&[1.0, 0.0]);
+        let result = Solver::new().bicgstab(&a, &b, 1e-10, 10);
+
+        assert_eq!(result.stop_reason(), StopReason::Breakdown);
+        assert!(result.solution().components.iter().all(|x| x.is_finite()));
+    }
+
+    // tridiagonal with constant diagonals, symmetric when lower == upper
+    fn tridiagonal(n: usize, lower: f64, diagonal: f64, upper: f64) -> Matrix {
+        let mut a = Matrix::zero(n, n);
+
+        for i in 0..n {
+            a.data[i * n + i] = diagonal;
+
+            if i + 1 < n {
+                a.data[i * n + i + 1] = upper;
+                a.data[(i + 1) * n + i] = lower;
+            }
+        }
+
+        a
+    }
+
+    fn residual(a: &Matrix, x: &Vector, b: &Vector) -> f64 {
+        (b - &(a * x)).magnitude()
+    }
+
+    #[test]
+    fn conjugate_gradient_converges_on_spd_systems() {
+        let n = 30;
+        let a = tridiagonal(n, -1.0, 2.0, -1.0);
+        let b = Vector::create_with_vec((1..=n).map(|i| (i as f64).sin()).collect());
+        let result = Solver::new().conjugate_gradient(&a, &b, 1e-10, 100);
+
+        assert_eq!(result.stop_reason(), StopReason::Converged);
+        assert!(result.iterations() <= n);
+        assert_eq!(result.residual_history().len(), result.iterations() + 1);
+        assert!(residual(&a, &result.solution(), &b) <= 1e-10 * b.magnitude());
+
+        let capped = Solver::new().conjugate_gradient(&a, &b, 1e-10, 5);
+
+        assert_eq!(capped.stop_reason(), StopReason::MaxIterations);
+        assert_eq!(capped.iterations(), 5);
+        assert_eq!(capped.residual_history().len(), 6);
+    }
+
+    #[test]
+    fn conjugate_gradient_detects_indefinite_matrices() {
+        let a = Matrix::new(&[&[1.0, 0.0], &[0.0, -1.0]]);
+        let b = Vector::new(&[0.0, 1.0]);
+        let result = Solver::new().conjugate_gradient(&a, &b, 1e-10, 10);
+
+        assert_eq!(result.stop_reason(), StopReason::Breakdown);
+        assert_eq!(result.residual_history().len(), result.iterations() + 1);
+    }
+
+    #[test]
+    fn restarted_gmres_converges_on_nonsymmetric_systems() {
+        let n = 40;
+        let a = tridiagonal(n, -1.4, 3.0, -0.5);
+        let b = Vector::create_with_vec((1..=n).map(|i| 1.0 + (i % 3) as f64).collect());
+        let result = Solver::new().gmres(&a, &b, 1e-10, 500, 5);
+
+        assert_eq!(result.stop_reason(), StopReason::Converged);
+        assert!(result.iterations() > 5);
+        assert_eq!(result.residual_history().len(), result.iterations() + 1);
+        assert!(residual(&a, &result.solution(), &b) <= 1e-10 * b.magnitude());
+        assert_eq!(result.residual_norm(), residual(&a, &result.solution(), &b));
+
+        let capped = Solver::new().gmres(&a, &b, 1e-10, 7, 5);
+
+        assert_eq!(capped.stop_reason(), StopReason::MaxIterations);
+        assert_eq!(capped.iterations(), 7);
+        assert_eq!(capped.residual_history().len(), 8);
+    }
+
+    #[test]
+    fn gmres_history_after_a_zero_rotation() {
+        // A e1 = 0, so the first arnoldi step has nothing to rotate
+        let a = Matrix::new(&[&[0.0, 1.0], &[0.0, 0.0]]);
+        let b = Vector::new(&[1.0, 0.0]);
+        let result = Solver::new().gmres(&a, &b, 1e-10, 10, 2);
+
+        assert_eq!(result.stop_reason(), StopReason::Breakdown);
+        assert_eq!(result.residual_history(), &[1.0, 1.0]);
+        assert_eq!(result.residual_history().len(), result.iterations() + 1);
+    }
+}
@@ -0,0 +1,608 @@
+use crate::*;
+
+// weight matrix W of min (A x - b)^T W (A x - b), usually the inverse of
+// the covariance of the errors in b
+pub enum Weights {
+    // one non negative weight per row of A
+    Diagonal(Vector),
+    // symmetric positive definite, m x m
+    Full(Matrix),
+}
+
+// solution of a least squares problem together with the norm of the
+// misfit it minimizes and the covariance of the estimate. the covariance
+// assumes errors in b with covariance W^-1 (the identity when there are
+// no weights). to use the noise level seen in the data instead, multiply
+// it by residual_norm^2 / (m - n)
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeastSquaresFit {
+    solution: Vector,
+    residual_norm: f64,
+    covariance: Matrix,
+}
+
+impl LeastSquaresFit {
+    pub fn solution(&self) -> &Vector {
+        &self.solution
+    }
+
+    pub fn residual_norm(&self) -> f64 {
+        self.residual_norm
+    }
+
+    pub fn covariance(&self) -> &Matrix {
+        &self.covariance
+    }
+
+    // square roots of the diagonal of the covariance
+    pub fn standard_errors(&self) -> Vector {
+        let n = self.solution.size();
+
+        Vector::create_with_vec((0..n).map(|i| self.covariance.data[i * n + i].sqrt()).collect())
+    }
+}
+
+// R^-1 for an n x n upper triangular R with nonzero diagonal
+fn upper_inverse(r: &[f64], n: usize) -> Vec<f64> {
+    let mut inverse = vec![0.0; n * n];
+
+    for j in 0..n {
+        inverse[j * n + j] = 1.0 / r[j * n + j];
+
+        for i in (0..j).rev() {
+            let sum = ((i + 1)..=j).map(|k| r[i * n + k] * inverse[k * n + j]).sum::<f64>();
+            inverse[i * n + j] = -sum / r[i * n + i];
+        }
+    }
+
+    inverse
+}
+
+fn qr_fit(matrix: &Matrix, b: &Vector) -> Result<LeastSquaresFit, LinalgError> {
+    // householder qr, A = Q R: x = R1^-1 (Q^T b)(1..n), the residual
+    // norm is |(Q^T b)(n + 1..m)| and (A^T A)^-1 = R1^-1 R1^-T. A needs
+    // full column rank, which is checked on the diagonal of R against
+    // the rounding of householder steps over columns of length m
+    let (m, n) = matrix.dimension();
+
+    if m < n {
+        return Err(LinalgError::DimensionMismatch {
+            expected: (n, n),
+            found: (m, n),
+        });
+    }
+
+    let qr = Decomposer::new().householder(matrix);
+    let c = qr.try_apply_qt(b)?.components;
+    let r = qr.get_thin_r().data;
+
+    let largest = (0..n).map(|i| r[i * n + i].abs()).fold(0.0, f64::max);
+
+    if (0..n).any(|i| r[i * n + i].abs() <= largest * m as f64 * f64::EPSILON) {
+        return Err(LinalgError::Singular);
+    }
+
+    let r_inverse = upper_inverse(&r, n);
+    let solution = (0..n).map(|i| (i..n).map(|k| r_inverse[i * n + k] * c[k]).sum::<f64>())
+                   .collect::<Vec<f64>>();
+    let residual_norm = c[n..].iter().map(|x| x * x).sum::<f64>().sqrt();
+
+    let mut covariance = vec![0.0; n * n];
+
+    for i in 0..n {
+        for j in 0..n {
+            covariance[i * n + j] = (i.max(j)..n).map(|k| r_inverse[i * n + k] * r_inverse[j * n + k])
+                                    .sum::<f64>();
+        }
+    }
+
+    Ok(LeastSquaresFit {
+        solution: Vector::create_with_vec(solution),
+        residual_norm,
+        covariance: Matrix::from_data(n, n, covariance),
+    })
+}
+
+fn check_rhs(matrix: &Matrix, b: &Vector) -> Result<(), LinalgError> {
+    let m = matrix.dimension().0;
+
+    if b.size() != m {
+        return Err(LinalgError::DimensionMismatch {
+            expected: (1, m),
+            found: b.dimension(),
+        });
+    }
+
+    Ok(())
+}
+
+fn norm(x: &Vector) -> f64 {
+    x.components.iter().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+impl LeastSquares {
+    pub fn fit(&self, matrix: &Matrix, b: &Vector) -> LeastSquaresFit {
+        self.try_fit(matrix, b).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_fit(&self, matrix: &Matrix, b: &Vector) -> Result<LeastSquaresFit, LinalgError> {
+        // ordinary least squares, A with full column rank
+        check_rhs(matrix, b)?;
+
+        qr_fit(matrix, b)
+    }
+
+    pub fn weighted(&self, matrix: &Matrix, b: &Vector, weights: &Weights) -> LeastSquaresFit {
+        self.try_weighted(matrix, b, weights).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_weighted
+    (
+        &self,
+        matrix: &Matrix,
+        b: &Vector,
+        weights: &Weights,
+    ) -> Result<LeastSquaresFit, LinalgError>
+    {
+        // with W = L L^T the problem is ordinary least squares for L^T A
+        // and L^T b, whose residual norm is sqrt(r^T W r). diagonal
+        // weights just scale the rows by sqrt(w_i)
+        check_rhs(matrix, b)?;
+
+        let (m, n) = matrix.dimension();
+
+        let (a, b) = match weights {
+            Weights::Diagonal(w) => {
+                if w.size() != m {
+                    return Err(LinalgError::DimensionMismatch {
+                        expected: (1, m),
+                        found: w.dimension(),
+                    });
+                }
+
+                if let Some(i) = w.components.iter().position(|&wi| wi < 0.0 || wi.is_nan()) {
+                    return Err(LinalgError::NotPositiveDefinite { pivot: i + 1 });
+                }
+
+                let scale = w.components.iter().map(|wi| wi.sqrt()).collect::<Vec<f64>>();
+                let mut a = matrix.clone();
+
+                for (row, s) in a.data.chunks_exact_mut(n.max(1)).zip(scale.iter()) {
+                    row.iter_mut().for_each(|x| *x *= s);
+                }
+
+                let b = b.components.iter().zip(scale.iter()).map(|(bi, s)| bi * s).collect();
+
+                (a, Vector::create_with_vec(b))
+            }
+            Weights::Full(w) => {
+                if w.dimension() != (m, m) {
+                    return Err(LinalgError::DimensionMismatch {
+                        expected: (m, m),
+                        found: w.dimension(),
+                    });
+                }
+
+                let lt = Decomposer::new().try_cholesky_factor(w, 0.0)?.l().transpose();
+
+                (&lt * matrix, lt.try_mul_vector(b)?)
+            }
+        };
+
+        qr_fit(&a, &b)
+    }
+
+    pub fn ridge(&self, matrix: &Matrix, b: &Vector, lambda: f64) -> LeastSquaresFit {
+        self.try_ridge(matrix, b, lambda).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_ridge(&self, matrix: &Matrix, b: &Vector, lambda: f64) -> Result<LeastSquaresFit, LinalgError> {
+        // min |A x - b|^2 + lambda |x|^2, the least squares problem of
+        // [A; sqrt(lambda) I] and [b; 0]. any lambda > 0 makes it well posed.
+        // the residual norm is |A x - b| without the penalty, and with
+        // M = A^T A + lambda I the covariance is M^-1 A^T A M^-1
+        if lambda < 0.0 || lambda.is_nan() {
+            return Err(LinalgError::InvalidParameter {
+                parameter: "lambda",
+                requirement: "non negative",
+            });
+        }
+
+        check_rhs(matrix, b)?;
+
+        let (m, n) = matrix.dimension();
+        let mut data = matrix.data.clone();
+        data.resize((m + n) * n, 0.0);
+
+        for i in 0..n {
+            data[(m + i) * n + i] = lambda.sqrt();
+        }
+
+        let mut rhs = b.components.clone();
+        rhs.resize(m + n, 0.0);
+
+        let fit = qr_fit(&Matrix::from_data(m + n, n, data), &Vector::create_with_vec(rhs))?;
+        let m_inverse = &fit.covariance;
+
+        // M^-1 A^T A M^-1 = M^-1 - lambda M^-2
+        let covariance = m_inverse - &(&(m_inverse * m_inverse) * lambda);
+        let residual_norm = norm(&(b - &(matrix * &fit.solution)));
+
+        Ok(LeastSquaresFit {
+            solution: fit.solution,
+            residual_norm,
+            covariance,
+        })
+    }
+
+    pub fn constrained
+    (
+        &self,
+        matrix: &Matrix,
+        b: &Vector,
+        constraints: &Matrix,
+        d: &Vector,
+    ) -> LeastSquaresFit
+    {
+        self.try_constrained(matrix, b, constraints, d).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_constrained
+    (
+        &self,
+        matrix: &Matrix,
+        b: &Vector,
+        constraints: &Matrix,
+        d: &Vector,
+    ) -> Result<LeastSquaresFit, LinalgError>
+    {
+        // min |A x - b| subject to C x = d, with C p x n of full row rank
+        // and [A; C] of full column rank. null space method: from
+        // C^T = Q [R; 0], every x = Q1 y1 + Q2 y2 with R^T y1 = d satisfies
+        // the constraints, and y2 is the ordinary least squares solution of
+        // A Q2 y2 = b - A Q1 y1. the covariance is Q2 Cov(y2) Q2^T
+        check_rhs(matrix, b)?;
+
+        let n = matrix.dimension().1;
+        let (p, columns) = constraints.dimension();
+
+        if columns != n || p > n {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (p.min(n), n),
+                found: (p, columns),
+            });
+        }
+
+        if d.size() != p {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (1, p),
+                found: d.dimension(),
+            });
+        }
+
+        let solver = Solver::new();
+        let qr = Decomposer::new().householder(&constraints.transpose());
+        let q = qr.get_q();
+        let r = qr.get_thin_r();
+
+        let largest = (0..p).map(|i| r.data[i * p + i].abs()).fold(0.0, f64::max);
+
+        if (0..p).any(|i| r.data[i * p + i].abs() <= largest * n as f64 * f64::EPSILON) {
+            return Err(LinalgError::Singular);
+        }
+
+        let y1 = solver.try_foward_substitution(&r.transpose(), d)?;
+        let q1 = Matrix::from_data(n, p, (1..=n).flat_map(|i| q.row_slice(i)[..p].to_vec()).collect());
+        let x1 = q1.try_mul_vector(&y1)?;
+
+        if p == n {
+            return Ok(LeastSquaresFit {
+                residual_norm: norm(&(b - &(matrix * &x1))),
+                solution: x1,
+                covariance: Matrix::zero(n, n),
+            });
+        }
+
+        let q2 = Matrix::from_data(n, n - p, (1..=n).flat_map(|i| q.row_slice(i)[p..].to_vec()).collect());
+        let fit = qr_fit(&(matrix * &q2), &(b - &(matrix * &x1)))?;
+
+        Ok(LeastSquaresFit {
+            solution: &x1 + &(&q2 * &fit.solution),
+            residual_norm: fit.residual_norm,
+            covariance: &(&q2 * &fit.covariance) * &q2.transpose(),
+        })
+    }
+
+    pub fn total(&self, matrix: &Matrix, b: &Vector) -> LeastSquaresFit {
+        self.try_total(matrix, b).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_total(&self, matrix: &Matrix, b: &Vector) -> Result<LeastSquaresFit, LinalgError> {
+        // errors in both A and b: the smallest [E f] in the frobenius norm
+        // with (A + E) x = b + f. x comes from the right singular vector v
+        // of [A b] for its smallest singular value sigma, x = -v(1..n) / v(n + 1),
+        // and the residual norm is |[E f]| = sigma. with unit variance errors
+        // in every entry the covariance is about (1 + |x|^2) (A^T A - sigma^2 I)^-1
+        check_rhs(matrix, b)?;
+
+        let (m, n) = matrix.dimension();
+
+        if m < n {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (n, n),
+                found: (m, n),
+            });
+        }
+
+        let decomposer = Decomposer::new();
+        let mut augmented = Vec::with_capacity(m * (n + 1));
+
+        for i in 1..=m {
+            augmented.extend_from_slice(matrix.row_slice(i));
+            augmented.push(b.components[i - 1]);
+        }
+
+        let svd = decomposer.try_svd(&Matrix::from_data(m, n + 1, augmented))?;
+        let sigma = svd.singular_values().components.get(n).copied().unwrap_or(0.0);
+        let vt = svd.full_vt();
+        let v = vt.row_slice(n + 1);
+
+        if v[n].abs() <= f64::EPSILON {
+            return Err(LinalgError::NoSolution);
+        }
+
+        let solution = Vector::create_with_vec(v[..n].iter().map(|vi| -vi / v[n]).collect());
+        let scale = 1.0 + solution.components.iter().map(|x| x * x).sum::<f64>();
+
+        // (A^T A - sigma^2 I)^-1 = sum v_i v_i^T / (s_i^2 - sigma^2) over the
+        // svd of A. the terms are positive exactly when the solution is unique
+        let svd = decomposer.try_svd(matrix)?;
+        let s = svd.singular_values().components;
+        let vt = svd.vt();
+        let mut covariance = Matrix::zero(n, n);
+
+        for (k, sk) in s.iter().enumerate() {
+            let gap = sk * sk - sigma * sigma;
+
+            if gap <= 0.0 {
+                return Err(LinalgError::NoSolution);
+            }
+
+            let vk = vt.row_slice(k + 1);
+
+            for i in 0..n {
+                for j in 0..n {
+                    covariance.data[i * n + j] += scale * vk[i] * vk[j] / gap;
+                }
+            }
+        }
+
+        Ok(LeastSquaresFit {
+            solution,
+            residual_norm: sigma,
+            covariance,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn max_difference(a: &Matrix, b: &Matrix) -> f64 {
+        assert_eq!(a.dimension(), b.dimension());
+        a.data.iter().zip(b.data.iter()).map(|(x, y)| (x - y).abs()).fold(0.0, f64::max)
+    }
+
+    fn close(x: &Vector, y: &Vector, tolerance: f64) -> bool {
+        x.size() == y.size() && (x - y).components.iter().all(|d| d.abs() <= tolerance)
+    }
+
+    fn design() -> Matrix {
+        Matrix::new(&[&[1.0, 0.0, 2.0],
+                      &[1.0, 1.0, -1.0],
+                      &[1.0, 2.0, 0.5],
+                      &[1.0, 3.0, 1.0],
+                      &[1.0, 4.0, -2.0]])
+    }
+
+    fn observations() -> Vector {
+        Vector::new(&[1.0, 2.5, 2.0, 4.5, 4.0])
+    }
+
+    #[test]
+    fn ordinary_fit_matches_the_normal_equations() {
+        let (a, b) = (design(), observations());
+        let fit = LeastSquares::new().fit(&a, &b);
+        let ata_inverse = (&a.transpose() * &a).inverse();
+        let x = &ata_inverse * &(&a.transpose() * &b);
+
+        assert!(close(fit.solution(), &x, 1e-12));
+        assert!((fit.residual_norm() - (&b - &(&a * &x)).magnitude()).abs() < 1e-12);
+        assert!(max_difference(fit.covariance(), &ata_inverse) < 1e-12);
+
+        let errors = Vector::create_with_vec((1..=3).map(|i| ata_inverse.element(i, i).unwrap().sqrt()).collect());
+
+        assert!(close(&fit.standard_errors(), &errors, 1e-12));
+    }
+
+    #[test]
+    fn diagonal_weights_equal_duplicated_rows() {
+        let (a, b) = (design(), observations());
+        let weights = Vector::new(&[1.0, 2.0, 1.0, 3.0, 1.0]);
+        let fit = LeastSquares::new().weighted(&a, &b, &Weights::Diagonal(weights.clone()));
+
+        let mut rows = vec![];
+        let mut rhs = vec![];
+
+        for i in 1..=5 {
+            for _ in 0..weights.components[i - 1] as usize {
+                rows.push(a.get_line(i).unwrap());
+                rhs.push(b.components[i - 1]);
+            }
+        }
+
+        let duplicated = LeastSquares::new().fit(&Matrix::create_with_vectors(&rows),
+                                                 &Vector::create_with_vec(rhs));
+
+        assert!(close(fit.solution(), duplicated.solution(), 1e-12));
+        assert!((fit.residual_norm() - duplicated.residual_norm()).abs() < 1e-12);
+        assert!(max_difference(fit.covariance(), duplicated.covariance()) < 1e-12);
+
+        // the same weights as a full matrix
+        let mut w = Matrix::zero(5, 5);
+
+        for i in 0..5 {
+            w.data[i * 5 + i] = weights.components[i];
+        }
+
+        let full = LeastSquares::new().weighted(&a, &b, &Weights::Full(w));
+
+        assert!(close(full.solution(), fit.solution(), 1e-12));
+        assert!((full.residual_norm() - fit.residual_norm()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn weighted_errors() {
+        let (a, b) = (design(), observations());
+        let solver = LeastSquares::new();
+        let negative = Weights::Diagonal(Vector::new(&[1.0, 1.0, -1.0, 1.0, 1.0]));
+        let nan = Weights::Diagonal(Vector::new(&[1.0, f64::NAN, 1.0, 1.0, 1.0]));
+        let mut indefinite = Matrix::identity(5);
+        indefinite.change_element(4, 4, -2.0);
+        let mut unsymmetric = Matrix::identity(5);
+        unsymmetric.change_element(1, 2, 0.5);
+
+        assert_eq!(solver.try_weighted(&a, &b, &negative), Err(LinalgError::NotPositiveDefinite { pivot: 3 }));
+        assert_eq!(solver.try_weighted(&a, &b, &nan), Err(LinalgError::NotPositiveDefinite { pivot: 2 }));
+        assert_eq!(solver.try_weighted(&a, &b, &Weights::Full(indefinite)),
+                   Err(LinalgError::NotPositiveDefinite { pivot: 4 }));
+        assert_eq!(solver.try_weighted(&a, &b, &Weights::Full(unsymmetric)), Err(LinalgError::NotSymmetric));
+        assert!(matches!(solver.try_weighted(&a, &b, &Weights::Full(Matrix::identity(4))),
+                         Err(LinalgError::DimensionMismatch { .. })));
+    }
+
+    #[test]
+    fn ridge_matches_the_regularized_normal_equations() {
+        let (a, b) = (design(), observations());
+        let lambda = 0.7;
+        let fit = LeastSquares::new().ridge(&a, &b, lambda);
+        let ata = &a.transpose() * &a;
+        let m_inverse = (&ata + &(&Matrix::identity(3) * lambda)).inverse();
+        let x = &m_inverse * &(&a.transpose() * &b);
+
+        assert!(close(fit.solution(), &x, 1e-12));
+        assert!((fit.residual_norm() - (&b - &(&a * &x)).magnitude()).abs() < 1e-12);
+        assert!(max_difference(fit.covariance(), &(&(&m_inverse * &ata) * &m_inverse)) < 1e-12);
+
+        let ordinary = LeastSquares::new().fit(&a, &b);
+        let unregularized = LeastSquares::new().ridge(&a, &b, 0.0);
+
+        assert!(close(unregularized.solution(), ordinary.solution(), 1e-12));
+        assert!(max_difference(unregularized.covariance(), ordinary.covariance()) < 1e-12);
+
+        // a penalty makes even rank deficient problems well posed
+        let deficient = Matrix::new(&[&[1.0, 2.0], &[2.0, 4.0], &[3.0, 6.0]]);
+
+        assert_eq!(LeastSquares::new().try_fit(&deficient, &Vector::new(&[1.0, 2.0, 3.0])),
+                   Err(LinalgError::Singular));
+        assert!(LeastSquares::new().try_ridge(&deficient, &Vector::new(&[1.0, 2.0, 3.0]), 0.1).is_ok());
+    }
+
+    #[test]
+    fn constrained_solutions_satisfy_the_constraints() {
+        let (a, b) = (design(), observations());
+        let c = Matrix::new(&[&[1.0, 1.0, 1.0]]);
+        let d = Vector::new(&[2.0]);
+        let fit = LeastSquares::new().constrained(&a, &b, &c, &d);
+
+        assert!(close(&(&c * fit.solution()), &d, 1e-13));
+
+        // kkt system [2 A^T A, C^T; C, 0] [x; mu] = [2 A^T b; d]
+        let ata = &a.transpose() * &a;
+        let atb = &a.transpose() * &b;
+        let mut kkt = Matrix::zero(4, 4);
+        let mut rhs = vec![0.0; 4];
+
+        for i in 0..3 {
+            for j in 0..3 {
+                kkt.data[i * 4 + j] = 2.0 * ata.data[i * 3 + j];
+            }
+
+            kkt.data[i * 4 + 3] = 1.0;
+            kkt.data[3 * 4 + i] = 1.0;
+            rhs[i] = 2.0 * atb.components[i];
+        }
+
+        rhs[3] = 2.0;
+
+        let kkt_solution = LuDecomposition::new(&kkt).solve(&Vector::create_with_vec(rhs));
+
+        assert!(close(fit.solution(), &Vector::create_with_vec(kkt_solution.components[..3].to_vec()), 1e-12));
+        assert!((fit.residual_norm() - (&b - &(&a * fit.solution())).magnitude()).abs() < 1e-12);
+
+        // the covariance has no spread along the constrained direction
+        assert!(close(&(&c * &fit.covariance().get_column(1).unwrap()), &Vector::new(&[0.0]), 1e-13));
+    }
+
+    #[test]
+    fn fully_constrained_solution() {
+        let (a, b) = (design(), observations());
+        let c = Matrix::new(&[&[1.0, 0.0, 1.0], &[0.0, 2.0, 1.0], &[1.0, 1.0, 0.0]]);
+        let d = Vector::new(&[1.0, 2.0, 3.0]);
+        let fit = LeastSquares::new().constrained(&a, &b, &c, &d);
+
+        assert!(close(&(&c * fit.solution()), &d, 1e-13));
+        assert!(close(fit.solution(), &(&c.inverse() * &d), 1e-13));
+        assert!(fit.covariance().is_zero());
+
+        let too_many = Matrix::new(&[&[1.0, 0.0, 0.0], &[0.0, 1.0, 0.0], &[0.0, 0.0, 1.0], &[1.0, 1.0, 1.0]]);
+
+        assert!(matches!(LeastSquares::new().try_constrained(&a, &b, &too_many, &Vector::new(&[1.0; 4])),
+                         Err(LinalgError::DimensionMismatch { .. })));
+    }
+
+    #[test]
+    fn total_least_squares_of_a_consistent_system() {
+        let a = design();
+        let x = Vector::new(&[0.5, -1.0, 2.0]);
+        let fit = LeastSquares::new().total(&a, &(&a * &x));
+
+        assert!(fit.residual_norm() < 1e-12);
+        assert!(close(fit.solution(), &x, 1e-12));
+
+        // with sigma = 0 the covariance is (1 + |x|^2) (A^T A)^-1
+        let expected = &(&a.transpose() * &a).inverse() * (1.0 + x.dot_product(&x));
+
+        assert!(max_difference(fit.covariance(), &expected) < 1e-11);
+
+        // noisy data: sigma is the smallest singular value of [A b]
+        let b = observations();
+        let fit = LeastSquares::new().total(&a, &b);
+        let mut rows = vec![];
+
+        for i in 1..=5 {
+            let mut row = a.get_line(i).unwrap().components;
+            row.push(b.components[i - 1]);
+            rows.push(Vector::create_with_vec(row));
+        }
+
+        let smallest = Decomposer::new().svd(&Matrix::create_with_vectors(&rows)).singular_values().components[3];
+
+        assert!((fit.residual_norm() - smallest).abs() < 1e-12);
+    }
+
+    #[test]
+    fn underdetermined_systems_are_rejected() {
+        let a = design().transpose();
+        let b = Vector::new(&[1.0, 2.0, 3.0]);
+        let mismatch = Err(LinalgError::DimensionMismatch { expected: (5, 5), found: (3, 5) });
+
+        assert_eq!(LeastSquares::new().try_fit(&a, &b), mismatch);
+        assert_eq!(LeastSquares::new().try_total(&a, &b), mismatch);
+        assert_eq!(LeastSquares::new().try_fit(&design(), &b),
+                   Err(LinalgError::DimensionMismatch { expected: (1, 5), found: (1, 3) }));
+        assert!(matches!(LeastSquares::new().try_ridge(&design(), &observations(), -1.0),
+                         Err(LinalgError::InvalidParameter { parameter: "lambda", .. })));
+    }
+}
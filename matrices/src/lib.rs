@@ -1,9 +1,11 @@
 #![allow(dead_code, unused_imports, unused_variables, 
-         unused_mut, non_snake_case, deprecated)]
+         unused_mut, non_snake_case, deprecated,
+         clippy::needless_range_loop)]
 
 extern crate vectors;
 
 use vectors::*;
+use complex::Complex;
 use std::ops::{Add, Sub, Mul};
 use std::fmt;
 use std::cmp::min;
@@ -11,192 +13,212 @@ use std::rc::Rc;
 use rand::Rng;
 use rayon::prelude::*;
 
+pub use errors::LinalgError;
+
+mod eigen;
+mod svd;
+mod hermitian;
+mod sparse;
+mod krylov;
+mod operator;
+mod preconditioner;
+mod gemm;
+mod row_operations;
+mod structured;
+mod householder;
+mod givens;
+mod cholesky;
+mod least_squares;
+pub use eigen::*;
+pub use svd::*;
+pub use sparse::*;
+pub use krylov::*;
+pub use operator::*;
+pub use preconditioner::*;
+pub use row_operations::*;
+pub use structured::*;
+pub use householder::*;
+pub use givens::*;
+pub use cholesky::*;
+pub use least_squares::*;
+
 #[derive(Debug, Clone, PartialEq)]
-pub struct Matrix {
-    rows: Vec<Vector>,
-    columns: Vec<Vector>,
+pub struct Matrix<T = f64> {
+    // elements are stored contiguously in row-major order,
+    // so the element (i, j) lives at data[(i - 1) * columns + (j - 1)]
+    rows: usize,
+    columns: usize,
+    data: Vec<T>,
 }
 
-impl Matrix {
-    pub fn new(numbers: &[&[f64]]) -> Self {
-        if numbers.len() == 0 {
-            panic!("tried to create a matrix with zero rows");
+impl<T: Scalar> Matrix<T> {
+    pub fn new(numbers: &[&[T]]) -> Self {
+        Self::try_new(numbers).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_new(numbers: &[&[T]]) -> Result<Self, LinalgError> {
+        if numbers.is_empty() {
+            return Err(LinalgError::Empty);
         }
 
-        let mut rows: Vec<Vector> = vec![];
         let fixed_size = numbers[0].len();
+        let mut data = Vec::with_capacity(numbers.len() * fixed_size);
 
         for &row in numbers.iter() {
-            assert!(row.len() == fixed_size,
-            "tried to create a matrix using rows of different sizes");
-
-            let mut components = vec![];
-
-            for &number in row.iter() {
-                components.push(number);
+            if row.len() != fixed_size {
+                return Err(LinalgError::DimensionMismatch {
+                    expected: (1, fixed_size),
+                    found: (1, row.len()),
+                });
             }
 
-            rows.push(Vector::new(&components));
+            data.extend_from_slice(row);
         }
 
-        let columns = Self::create_columns(numbers);
+        Ok(Self {
+            rows: numbers.len(),
+            columns: fixed_size,
+            data,
+        })
+    }
+
+    fn from_data(rows: usize, columns: usize, data: Vec<T>) -> Self {
+        assert!(data.len() == rows * columns);
 
         Self {
             rows,
             columns,
+            data,
         }
     }
 
-    pub fn ortogonal_projector(&self, vector: bool) -> Self {
-        if vector {
-            return &(&self.transpose() * self) *
-            (1.0 / (self * &self.transpose()).element(1, 1).unwrap());
+    fn index(&self, row: usize, column: usize) -> usize {
+        (row - 1) * self.columns + (column - 1)
+    }
+
+    fn check_position(&self, row: usize, column: usize) -> Result<(), LinalgError> {
+        if row == 0 || column == 0 || row > self.rows || column > self.columns {
+            return Err(LinalgError::IndexOutOfBounds {
+                index: (row, column),
+                dimension: self.dimension(),
+            });
         }
 
-        let s = Spaces::new();
+        Ok(())
+    }
 
-        let range_dimension = s.column_dimension(self);
-        let (_, n) = self.dimension();
+    fn check_same_dimension(&self, other: &Self) -> Result<(), LinalgError> {
+        if self.dimension() != other.dimension() {
+            return Err(LinalgError::DimensionMismatch {
+                expected: self.dimension(),
+                found: other.dimension(),
+            });
+        }
 
-        if range_dimension != n {
-            panic!("there is no ortogonal projector");
+        Ok(())
+    }
+
+    fn check_square(&self) -> Result<(), LinalgError> {
+        let (m, n) = self.dimension();
+
+        if m != n {
+            return Err(LinalgError::NotSquare { rows: m, columns: n });
         }
 
-        let tranpose = self.transpose();
-        let t_m = &tranpose * self;
-        let t_m_inverse = t_m.inverse();
+        Ok(())
+    }
 
-        self * &( &t_m_inverse * &self.transpose())
+    fn row_slice(&self, row: usize) -> &[T] {
+        let start = (row - 1) * self.columns;
+        &self.data[start..start + self.columns]
     }
 
     pub fn zero(rows: usize, columns: usize) -> Self {
-        let result = (0..rows)
-                .map(|i| Vector::zero(columns))
-                .collect::<Vec<Vector>>();
-
-        Self::create_with_vectors(&result)
+        Self::from_data(rows, columns, vec![T::zero(); rows * columns])
     }
 
     pub fn is_zero(&self) -> bool {
-        for row in self.rows.iter() {
-            if !row.is_zero() {
-                return false;
-            }
-        }
-
-        true
+        self.data.iter().all(|element| element.is_zero())
     }
 
     pub fn identity(size: usize) -> Self {
-        let mut result = vec![];
+        let mut result = Self::zero(size, size);
 
         for i in 0..size {
-            let mut components = vec![];
-
-            for j in 0..size {
-                if i == j {
-                    components.push(1.0);
-                } else {
-                    components.push(0.0);
-                }
-            }
-
-            result.push(Vector::new(&components));
+            result.data[i * size + i] = T::one();
         }
 
-        Matrix::create_with_vectors(&result)
+        result
     }
 
-    pub fn sign(&self, i: usize, j: usize) -> f64 {
-        assert!(i > 0 && j > 0);
-        let (m, n) = self.dimension();
-        assert!(i <= m && j <= n);
-
-        let element = self.element(i, j).unwrap();
+    pub fn create_with_vectors(rows: &[Vector<T>]) -> Self {
+        Self::try_create_with_vectors(rows).unwrap_or_else(|error| panic!("{}", error))
+    }
 
-        if element == 0.0 {
-            return 0.0;
-        } else if element < 0.0 {
-            return -1.0;
-        } else {
-            return 1.0;
+    pub fn try_create_with_vectors(rows: &[Vector<T>]) -> Result<Self, LinalgError> {
+        if rows.is_empty() {
+            return Err(LinalgError::Empty);
         }
-    }
 
-    pub fn create_with_vectors(rows: &[Vector]) -> Self {
-        let mut columns: Vec<Vector> = vec![];
         let row_length = rows[0].size();
-
-        for j in 0..row_length {
-            let mut components = vec![];
-
-            for i in 0..rows.len() {
-                components.push(
-                    rows[i].element(j+1).expect(
-                    "tried to create a matrice using rows of different sizes"
-                    )
-                )
+        let mut data = Vec::with_capacity(rows.len() * row_length);
+
+        for row in rows.iter() {
+            if row.size() != row_length {
+                return Err(LinalgError::DimensionMismatch {
+                    expected: rows[0].dimension(),
+                    found: row.dimension(),
+                });
             }
 
-            columns.push(Vector::new(&components));
+            data.extend_from_slice(&row.components);
         }
 
-        Self {
-            rows: rows.to_vec(),
-            columns,
-        }
+        Ok(Self::from_data(rows.len(), row_length, data))
     }
 
-    fn create_columns(numbers: &[&[f64]]) -> Vec<Vector> {
-        let mut columns: Vec<Vector> = vec![];
-        let row_length = numbers[0].len();
-
-        for j in 0..row_length {
-            let mut components = vec![];
+    pub fn sub_matrix(&self, (i_i, i_e) : (usize, usize), (j_i, j_e) : (usize, usize)) -> Self {
+        self.try_sub_matrix((i_i, i_e), (j_i, j_e))
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
 
-            for i in 0..numbers.len() {
-                components.push(numbers[i][j]);
-            }
+    pub fn try_sub_matrix
+    (
+        &self,
+        (i_i, i_e) : (usize, usize),
+        (j_i, j_e) : (usize, usize)
+    ) -> Result<Self, LinalgError>
+    {
+        self.check_position(i_i, j_i)?;
+        self.check_position(i_e, j_e)?;
 
-            columns.push(Vector::new(&components));
+        if i_i > i_e || j_i > j_e {
+            return Err(LinalgError::Empty);
         }
 
-        columns
-    }
-
-    pub fn sub_matrix(&self, (i_i, i_e) : (usize, usize), (j_i, j_e) : (usize, usize)) -> Matrix {
         let (m, n) = (i_e - i_i + 1, j_e - j_i + 1);
-        let mut sub_matrix = Matrix::zero(m, n);
+        let mut data = Vec::with_capacity(m * n);
 
         for i in i_i..=i_e {
-            for j in j_i..=j_e {
-                if let Some(element) = self.element(i, j) {
-                    sub_matrix.change_element(i - i_i + 1, j - j_i + 1, element);
-                }
-            }
+            data.extend_from_slice(&self.row_slice(i)[(j_i - 1)..j_e]);
         }
 
-        sub_matrix
+        Ok(Self::from_data(m, n, data))
     }
 
-    pub fn sub_vector(&self, (i_i, i_e): (usize, usize), k: usize) -> Vector {
-        let m = i_e - i_i + 1;
-        let mut sub_vec = Vector::zero(m);
-
-        for i in i_i..=i_e {
-            if let Some(element) = self.element(i, k) {
-                sub_vec.change_element(i - i_i + 1, element);
-            }
-        }
+    pub fn sub_vector(&self, (i_i, i_e): (usize, usize), k: usize) -> Vector<T> {
+        let components = (i_i..=i_e)
+                         .map(|i| self.data[self.index(i, k)].clone())
+                         .collect::<Vec<T>>();
 
-        sub_vec
+        Vector::create_with_vec(components)
     }
 
     pub fn multiply_by_sub
     (   &mut self, 
         (i_i, i_e): (usize, usize),
         (j_i, j_e): (usize, usize),
-        other: &Matrix,       
+        other: &Self,       
     )
     {
         let sub_matrix = self.sub_matrix((i_i, i_e), (j_i, j_e));
@@ -204,9 +226,8 @@ impl Matrix {
 
         for i in i_i..=i_e {
             for j in j_i..=j_e {
-                if let Some(element) = result.element(i - i_i + 1, j - j_i + 1) {
-                    self.change_element(i, j, element);
-                }
+                let index = self.index(i, j);
+                self.data[index] = result.data[result.index(i - i_i + 1, j - j_i + 1)].clone();
             }
         }
     }
@@ -215,126 +236,113 @@ impl Matrix {
         &mut self, 
         (i_i, i_e): (usize, usize),
         (j_i, j_e): (usize, usize),
-        other: &Matrix,       
+        other: &Self,       
     )
     {   
         for i in i_i..=i_e {
             for j in j_i..=j_e {
-                let element_self = self.element(i, j).unwrap();
-                let element_other = other.element(i - i_i + 1, j - j_i + 1).unwrap();
-
-                self.change_element(i, j, element_self - element_other);
+                let index = self.index(i, j);
+                self.data[index] = self.data[index].clone()
+                                   - other.element(i - i_i + 1, j - j_i + 1).unwrap();
             }
         }
     }
 
-    fn change_rows_columns(&mut self) {
-        let mut rows: Vec<Vector> = vec![];
-        let mut columns: Vec<Vector> = vec![];
-        let (m, n) = self.dimension();
-
-        for j in 1..=n {
-            let mut components = vec![];
-
-            for i in 1..=m {
-                components.push(self.element(i, j).unwrap());
-            }
-
-            columns.push(Vector::new(&components));
-        }
-
-        for i in 1..=m {
-            let mut components = vec![];
+    pub fn get_line(&self, position: usize) -> Option<Vector<T>> {
+        assert!(position > 0, "position must be greater than zero");
 
-            for j in 1..=n {
-                components.push(self.element(i, j).unwrap());
-            }
+        self.try_get_line(position).ok()
+    }
 
-            rows.push(Vector::new(&components));
-        }
+    pub fn try_get_line(&self, position: usize) -> Result<Vector<T>, LinalgError> {
+        self.check_position(position, 1)?;
 
-        self.rows = rows;
-        self.columns = columns;
+        Ok(Vector::create_with_vec(self.row_slice(position).to_vec()))
     }
 
-    pub fn get_line(&self, position: usize) -> Option<Vector> {
+    pub fn get_column(&self, position: usize) -> Option<Vector<T>> {
         assert!(position > 0, "position must be greater than zero");
 
-        match self.rows.get(position - 1) {
-            Some(&ref vector) => Some(vector.clone()),
-            None => None,
-        }
+        self.try_get_column(position).ok()
     }
 
-    pub fn get_column(&self, position: usize) -> Option<Vector> {
-        assert!(position > 0, "position must be greater than zero");
+    pub fn try_get_column(&self, position: usize) -> Result<Vector<T>, LinalgError> {
+        self.check_position(1, position)?;
 
-        match self.columns.get(position -1) {
-            Some(&ref vector) => Some(vector.clone()),
-            None => None,
-        }
+        let components = self.data.iter()
+                         .skip(position - 1)
+                         .step_by(self.columns)
+                         .cloned()
+                         .collect::<Vec<T>>();
+
+        Ok(Vector::create_with_vec(components))
+    }
+
+    pub fn change_column(&mut self, position: usize, column: Vector<T>) {
+        self.try_change_column(position, column)
+            .unwrap_or_else(|error| panic!("{}", error))
     }
 
-    pub fn change_column(&mut self, position: usize, column: Vector) {
+    pub fn try_change_column
+    (
+        &mut self,
+        position: usize,
+        column: Vector<T>
+    ) -> Result<(), LinalgError>
+    {
         let (m, n) = self.dimension();
-        assert!(column.size() == m);
+        self.check_position(1, position)?;
+
+        if column.size() != m {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (1, m),
+                found: column.dimension(),
+            });
+        }
 
-        for i in 1..=m {
-            self.change_element(i, position, column.element(i).unwrap());
+        for (i, element) in column.components.into_iter().enumerate() {
+            self.data[i * n + position - 1] = element;
         }
 
+        Ok(())
     }
 
     pub fn dimension(&self) -> (usize, usize) {
-        (self.rows.len(), self.rows[0].size())
+        (self.rows, self.columns)
     }
 
     pub fn transpose(&self) -> Self {
-        let cloned = self.clone();
+        let (m, n) = self.dimension();
+        let mut data = Vec::with_capacity(m * n);
 
-        let result = cloned.columns.into_iter()
-            .map(|vector| vector)
-            .collect::<Vec<Vector>>();
+        for j in 0..n {
+            for i in 0..m {
+                data.push(self.data[i * n + j].clone());
+            }
+        }
 
-        Matrix::create_with_vectors(&result)
+        Self::from_data(n, m, data)
     }
 
-    pub fn element(&self, row: usize, column: usize) -> Option<f64> {
-        let (r, c) = self.dimension();
-
-        assert!(row > 0 && column > 0, 
-                "either row or column is non-positive");
+    pub fn element(&self, row: usize, column: usize) -> Option<T> {
+        Some(self.try_element(row, column).unwrap_or_else(|error| panic!("{}", error)))
+    }
 
-        assert!(row <= r && column <= c,
-                "either row or column is greater than the limit");
+    pub fn try_element(&self, row: usize, column: usize) -> Result<T, LinalgError> {
+        self.check_position(row, column)?;
 
-        if let Some(vector) = self.get_line(row) {
-            if let Some(number) = vector.element(column) {
-                return Some(number);
-            } else {
-                return None;
-            }
-        } else {
-            return None
-        }
+        Ok(self.data[self.index(row, column)].clone())
     }
 
-    pub fn get_sub_column(&self, init: usize, end: usize, column: usize) -> Vector {
+    pub fn get_sub_column(&self, init: usize, end: usize, column: usize) -> Vector<T> {
         let (m, n) = self.dimension();
         assert!(init <= end && init > 0  && end > 0 && column > 0);
         assert!(init <= m && end <= m && column <= n);
 
-        let mut vector = Vector::zero(end - init + 1);
-
-        for i in init..=end {
-            let element = self.element(i, column).unwrap();
-            vector.change_element(i + 1 - init, element);
-        }
-
-        vector
+        self.sub_vector((init, end), column)
     }
 
-    pub fn create_with_diagonal(matrices: &[Matrix]) -> Matrix {
+    pub fn create_with_diagonal(matrices: &[Self]) -> Self {
         let mut m = 0;
         let mut n = 0;
 
@@ -344,14 +352,18 @@ impl Matrix {
             n += y;
         }
 
-        let mut new_matrix = Matrix::zero(m, n);
+        let mut new_matrix = Self::zero(m, n);
+
+        let (mut x, mut y) = (0, 0);
+        for matrix in matrices {
+            let (x1, y1) = matrix.dimension();
 
-        let (mut x, mut y) = (1, 1);
-        for i in 0..matrices.len() {
-            let (x1, y1) = matrices[i].dimension();
+            for i in 0..x1 {
+                let start = (x + i) * n + y;
+                new_matrix.data[start..start + y1]
+                    .clone_from_slice(&matrix.data[i * y1..(i + 1) * y1]);
+            }
 
-            new_matrix.subtract_by_sub((x, x + x1 - 1), (y, y + y1 - 1), 
-            &(-1.0*&matrices[i]));
             x += x1;
             y += y1;
         }
@@ -364,103 +376,201 @@ impl Matrix {
         &mut self, 
         row: usize, 
         column: usize,
-        element: f64,
+        element: T,
     )
     {
-        let (r, c) = self.dimension();
+        self.try_change_element(row, column, element)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }   
 
-        assert!(row > 0 && column > 0, 
-                "either row or column is non-positive");
+    pub fn try_change_element
+    (
+        &mut self,
+        row: usize,
+        column: usize,
+        element: T,
+    ) -> Result<(), LinalgError>
+    {
+        self.check_position(row, column)?;
 
-        assert!(row <= r && column <= c,
-                "either row or column is greater than the limit");
+        let index = self.index(row, column);
+        self.data[index] = element;
 
-        self.rows[row - 1].change_element(column, element);
-        self.change_rows_columns();
-    }   
+        Ok(())
+    }
 
-    pub fn inverse(&self) -> Matrix {
-        let (m, n) = self.dimension();
+    pub fn inverse(&self) -> Self {
+        self.try_inverse().unwrap_or_else(|error| panic!("{}", error))
+    }
 
-        if m != n {
-            panic!("matrix is not square");
+    pub fn try_inverse(&self) -> Result<Self, LinalgError> {
+        self.check_square()?;
+
+        let lu = LuDecomposition::new(self);
+
+        if lu.is_singular() {
+            return Err(LinalgError::Singular);
         }
 
-        let eliminator = Eliminator::new();
-        let spaces = Spaces::new();
+        lu.try_inverse()
+    }
 
-        if spaces.null_dimension(self) != 0 {
-            panic!("matrix is singular");
+    pub fn determinant(&self) -> T {
+        self.try_determinant().unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_determinant(&self) -> Result<T, LinalgError> {
+        self.check_square()?;
+
+        Ok(LuDecomposition::new(self).determinant())
+    }
+
+    pub fn try_add(&self, other: &Self) -> Result<Self, LinalgError> {
+        self.check_same_dimension(other)?;
+
+        let data = self.data.iter()
+                   .zip(other.data.iter())
+                   .map(|(a, b)| a.clone() + b.clone())
+                   .collect::<Vec<T>>();
+
+        Ok(Self::from_data(self.rows, self.columns, data))
+    }
+
+    pub fn try_sub(&self, other: &Self) -> Result<Self, LinalgError> {
+        self.check_same_dimension(other)?;
+
+        let data = self.data.iter()
+                   .zip(other.data.iter())
+                   .map(|(a, b)| a.clone() - b.clone())
+                   .collect::<Vec<T>>();
+
+        Ok(Self::from_data(self.rows, self.columns, data))
+    }
+
+    pub fn try_mul_vector(&self, other: &Vector<T>) -> Result<Vector<T>, LinalgError> {
+        if other.size() != self.columns {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (1, self.columns),
+                found: other.dimension(),
+            });
         }
 
-        let mut cloned = self.clone();
-        let (_, inverse) = eliminator.rref(&mut cloned);
+        let result = self.data.chunks_exact(self.columns)
+                     .map(|row| row.iter()
+                                   .zip(other.components.iter())
+                                   .fold(T::zero(), |sum, (a, b)| sum + a.clone() * b.clone()))
+                     .collect::<Vec<T>>();
 
-        inverse
+        Ok(Vector::create_with_vec(result))
     }
-}
 
-impl fmt::Display for Matrix {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "")?;
-        for (i, vector) in self.rows.iter().enumerate() {
-            if i == self.rows.len() - 1 {
-                write!(f, "{}", vector)?;
-            } else {
-                write!(f, "{}\n", vector)?;
-            }
+    pub fn try_mul(&self, other: &Self) -> Result<Self, LinalgError> {
+        let (r1, c1) = self.dimension();
+        let (r2, c2) = other.dimension();
+
+        if c1 != r2 {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (r1, c1),
+                found: (r2, c2),
+            });
         }
-        write!(f, "")
+
+        Ok(self.blocked_product(other))
     }
 }
 
-impl Add<&Matrix> for &Matrix {
-    type Output = Matrix;
+impl Matrix {
+    pub fn ortogonal_projector(&self, vector: bool) -> Self {
+        if vector {
+            return &(&self.transpose() * self) *
+            (1.0 / (self * &self.transpose()).element(1, 1).unwrap());
+        }
+
+        let s = Spaces::new();
 
-    fn add(self, other: &Matrix) -> Matrix {
-        let length = self.rows.len();
+        let range_dimension = s.column_dimension(self);
+        let (_, n) = self.dimension();
 
-        if length != other.rows.len() {
-            panic!("tried to add matrices of different sizes");
+        if range_dimension != n {
+            panic!("there is no ortogonal projector");
         }
 
-        let result = self.rows.iter()
-                    .zip(other.rows.iter())
-                    .map(|(vector1, vector2)| vector1 + vector2)
-                    .collect::<Vec<Vector>>();
+        let tranpose = self.transpose();
+        let t_m = &tranpose * self;
+        let t_m_inverse = t_m.inverse();
 
-        Matrix::create_with_vectors(&result)
+        self * &( &t_m_inverse * &self.transpose())
     }
-}
 
-impl Sub<&Matrix> for &Matrix {
-    type Output = Matrix;
+    pub fn sign(&self, i: usize, j: usize) -> f64 {
+        assert!(i > 0 && j > 0);
+        let (m, n) = self.dimension();
+        assert!(i <= m && j <= n);
 
-    fn sub(self, other: &Matrix) -> Matrix {
-        let length = self.rows.len();
+        let element = self.element(i, j).unwrap();
 
-        if length != other.rows.len() {
-            panic!("tried to sub matrices of different sizes");
+        if element == 0.0 {
+            0.0
+        } else if element < 0.0 {
+            -1.0
+        } else {
+            1.0
         }
+    }
 
-        let result = self.rows.iter()
-                    .zip(other.rows.iter())
-                    .map(|(vector1, vector2)| vector1 - vector2)
-                    .collect::<Vec<Vector>>();
+    pub fn log_abs_determinant(&self) -> (f64, f64) {
+        self.try_log_abs_determinant().unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_log_abs_determinant(&self) -> Result<(f64, f64), LinalgError> {
+        // returns (sign, ln|det|), which doesn't overflow for large matrices
+        self.check_square()?;
 
-        Matrix::create_with_vectors(&result)
+        Ok(LuDecomposition::new(self).log_abs_determinant())
     }
 }
 
-impl Mul<f64> for &Matrix {
-    type Output = Matrix;
+impl<T: Scalar> fmt::Display for Matrix<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for i in 1..=self.rows {
+            let vector = self.get_line(i).unwrap();
+
+            if i == self.rows {
+                write!(f, "{}", vector)?;
+            } else {
+                writeln!(f, "{}", vector)?;
+            }
+        }
+
+        Ok(())
+    }
+}
 
-    fn mul(self, other: f64) -> Matrix {
-        let result = self.rows.iter()
-                    .map(|vector| vector * other)
-                    .collect::<Vec<Vector>>();
+impl<T: Scalar> Add<&Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
 
-        Matrix::create_with_vectors(&result)
+    fn add(self, other: &Matrix<T>) -> Matrix<T> {
+        self.try_add(other).unwrap_or_else(|error| panic!("{}", error))
+    }
+}
+
+impl<T: Scalar> Sub<&Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn sub(self, other: &Matrix<T>) -> Matrix<T> {
+        self.try_sub(other).unwrap_or_else(|error| panic!("{}", error))
+    }
+}
+
+impl<T: Scalar> Mul<T> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, other: T) -> Matrix<T> {
+        let data = self.data.iter()
+                   .map(|a| a.clone() * other.clone())
+                   .collect::<Vec<T>>();
+
+        Matrix::from_data(self.rows, self.columns, data)
     }
 }
 
@@ -468,158 +578,314 @@ impl Mul<&Matrix> for f64 {
     type Output = Matrix;
 
     fn mul(self, other: &Matrix) -> Matrix {
-        let result = other.rows.iter()
-                    .map(|vector| vector * self)
-                    .collect::<Vec<Vector>>();
-                    
-        Matrix::create_with_vectors(&result)
+        other * self
     }
 }
 
-impl Mul<&Vector> for &Matrix {
-    type Output = Vector;
-
-    fn mul(self, other: &Vector) -> Self::Output {
-        let matrix_columns = self.dimension().1;
-        let vector_size = other.size();
+impl Mul<&Matrix<f32>> for f32 {
+    type Output = Matrix<f32>;
 
-        assert!(vector_size == matrix_columns, 
-                "invalid multiplication");
+    fn mul(self, other: &Matrix<f32>) -> Matrix<f32> {
+        other * self
+    }
+}
 
-        let result = self.rows.iter()
-                     .map(|vector| vector.dot_product(&other))
-                     .collect::<Vec<f64>>();
+impl Mul<&Matrix<Complex>> for Complex {
+    type Output = Matrix<Complex>;
 
-        Vector::create_with_vec(result)
+    fn mul(self, other: &Matrix<Complex>) -> Matrix<Complex> {
+        other * self
     }
 }
 
-impl Mul<&Matrix> for &Matrix {
-    type Output = Matrix;
+impl<T: Scalar> Mul<&Vector<T>> for &Matrix<T> {
+    type Output = Vector<T>;
 
-    fn mul(self, other: &Matrix) -> Self::Output {
-        let (r1, c1) = self.dimension();
-        let (r2, c2) = other.dimension();
+    fn mul(self, other: &Vector<T>) -> Self::Output {
+        self.try_mul_vector(other).unwrap_or_else(|error| panic!("{}", error))
+    }
+}
+
+impl<T: Scalar> Mul<&Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
 
-        assert!(c1 == r2, "invalid multiplication");
+    fn mul(self, other: &Matrix<T>) -> Self::Output {
+        self.try_mul(other).unwrap_or_else(|error| panic!("{}", error))
+    }
+}
 
-        let result = other.columns.iter()
-                     .map(|vector| self * vector)
-                     .collect::<Vec<Vector>>();
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PivotStrategy {
+    // first entry of the column above the tolerance
+    FirstNonZero,
+    // largest entry of the column
+    #[default]
+    Partial,
+    // largest entry of the whole remaining submatrix, swapping columns
+    Complete,
+    // an entry that is the largest of both its row and its column,
+    // found by alternating searches. also swaps columns
+    Rook,
+}
 
-        (Matrix::create_with_vectors(&result)).transpose()
+impl PivotStrategy {
+    fn swaps_columns(&self) -> bool {
+        matches!(self, PivotStrategy::Complete | PivotStrategy::Rook)
     }
 }
 
-pub struct Eliminator {}
+// entries with absolute value up to the tolerance are never chosen
+// as pivots and count as zeros
+#[derive(Default)]
+pub struct Eliminator {
+    pivoting: PivotStrategy,
+    tolerance: f64,
+}
 
 impl Eliminator {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            pivoting: PivotStrategy::Partial,
+            tolerance: 0.0,
+        }
+    }
+
+    pub fn with_pivoting(pivoting: PivotStrategy, tolerance: f64) -> Self {
+        Self {
+            pivoting,
+            tolerance,
+        }
+    }
+
+    pub fn pivoting(&self) -> PivotStrategy {
+        self.pivoting
+    }
+
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
     }
 
-    pub fn swap_rows
+    pub fn swap_rows<T: Scalar>
     (
         &self,
         row1: usize,
         row2: usize,
-        matrix: &mut Matrix,
+        matrix: &mut Matrix<T>,
     )
     {
-        let (rows, _) = matrix.dimension();
+        let (rows, n) = matrix.dimension();
 
         assert!(row1 <= rows && row2 <= rows && row1 > 0 && row2 > 0);
 
-        let mut permutation_matrix = Matrix::identity(rows);
-        permutation_matrix.change_element(row1, row1, 0.0);
-        permutation_matrix.change_element(row1, row2, 1.0);
-        permutation_matrix.change_element(row2, row2, 0.0);
-        permutation_matrix.change_element(row2, row1, 1.0);
+        if row1 == row2 {
+            return;
+        }
+
+        let (first, second) = (min(row1, row2) - 1, row1.max(row2) - 1);
+        let (upper, lower) = matrix.data.split_at_mut(second * n);
+
+        upper[first * n..(first + 1) * n].swap_with_slice(&mut lower[..n]);
+    }
+
+    pub fn swap_columns<T: Scalar>
+    (
+        &self,
+        column1: usize,
+        column2: usize,
+        matrix: &mut Matrix<T>,
+    )
+    {
+        let (_, columns) = matrix.dimension();
 
-        let cloned = matrix.clone();
+        assert!(column1 <= columns && column2 <= columns && column1 > 0 && column2 > 0);
 
-        *matrix = &permutation_matrix * &cloned;
+        for row in matrix.data.chunks_exact_mut(columns) {
+            row.swap(column1 - 1, column2 - 1);
+        }
     }
 
-    pub fn multiply_row_by_number
+    pub fn multiply_row_by_number<T: Scalar>
     (
         &self,
         row: usize,
-        factor: f64,
-        matrix: &mut Matrix,
+        factor: T,
+        matrix: &mut Matrix<T>,
     )
     {
-        let (rows, _) = matrix.dimension();
+        let (rows, n) = matrix.dimension();
 
         assert!(row <= rows);
         assert!(row > 0);
 
-        let mut elimination_matrix = Matrix::identity(rows);
-        elimination_matrix.change_element(row, row, factor);
-
-        let cloned = matrix.clone();
-
-        *matrix = &elimination_matrix * &cloned;
+        for x in matrix.data[(row - 1) * n..row * n].iter_mut() {
+            *x = factor.clone() * x.clone();
+        }
     }
 
-    pub fn multiply_rows // re = re - factor * ru
+    pub fn multiply_rows<T: Scalar> // re = re - factor * ru
     (   
         &self,
         re: usize, 
         ru: usize,
-        fu: f64,
-        matrix: &mut Matrix,
+        fu: T,
+        matrix: &mut Matrix<T>,
     )
     {   
-        let (rows, _) = matrix.dimension();
+        let (rows, n) = matrix.dimension();
 
         assert!(re <= rows && ru <= rows);
         assert!(re > 0 && ru > 0);
         assert!(re != ru);
 
-        let mut elimination_matrix = Matrix::identity(rows);
-        elimination_matrix.change_element(re, ru, -fu);
+        let (target, source) = if re < ru {
+            let (upper, lower) = matrix.data.split_at_mut((ru - 1) * n);
+            (&mut upper[(re - 1) * n..re * n], &lower[..n])
+        } else {
+            let (upper, lower) = matrix.data.split_at_mut((re - 1) * n);
+            (&mut lower[..n], &upper[(ru - 1) * n..ru * n])
+        };
 
-        let cloned = matrix.clone();
+        for (t, s) in target.iter_mut().zip(source.iter()) {
+            *t = t.clone() - fu.clone() * s.clone();
+        }
+    }
 
-        *matrix = &elimination_matrix * &cloned;
+    pub fn row_echelon_form<T: Scalar>(&self, matrix: &mut Matrix<T>) -> Vec<(usize, usize)> {
+        self.row_echelon_form_tol(matrix, self.tolerance)
     }
 
-    pub fn row_echelon_form
+    pub fn row_echelon_form_tol<T: Scalar>
     (
         &self,
-        matrix: &mut Matrix
-    ) -> (Vec<(usize, usize)>, Matrix)
+        matrix: &mut Matrix<T>,
+        tolerance: f64,
+    ) -> Vec<(usize, usize)>
     {
-        let mut h = 1;
-        let mut k = 1;
-
-        let (m, n) = matrix.dimension();
-        let mut leading_ones = vec![];
-        let mut to_inverse = Matrix::identity(m);
+        let n = matrix.dimension().1;
 
-        while h <= m && k <= n {
-            let column = matrix.get_column(k).unwrap();
-            let mut index = 0;
+        self.echelon(matrix, n, tolerance, None)
+    }
 
-            for i in (h-1)..n {
-                if m < n && i == n - 1 {
-                    break;
-                }
+    // also returns the operations that took the matrix to its echelon
+    // form E A, which can be replayed on other matrices or vectors.
+    // with complete or rook pivoting the result is E A Q instead, where
+    // Q is the column permutation that is recorded too
+    pub fn recorded_row_echelon_form<T: Scalar>
+    (
+        &self,
+        matrix: &mut Matrix<T>,
+        tolerance: f64,
+    ) -> (Vec<(usize, usize)>, RowOperations<T>)
+    {
+        let (m, n) = matrix.dimension();
+        let mut operations = RowOperations::new(m, n);
+        let pivots = self.echelon(matrix, n, tolerance, Some(&mut operations));
 
-                if column.components[i] != 0.0 {
-                    index = i + 1;
-                    break;
-                }
-            }
+        (pivots, operations)
+    }
+
+    fn find_pivot<T: Scalar>
+    (
+        &self,
+        matrix: &Matrix<T>,
+        (h, k): (usize, usize),
+        last_column: usize,
+        tolerance: f64,
+    ) -> Option<(usize, usize)>
+    {
+        // pivot for position (h, k), only looking at columns up to last_column
+        let (m, n) = matrix.dimension();
+        let modulus = |i: usize, j: usize| matrix.data[(i - 1) * n + j - 1].modulus();
+        let column_max = |j: usize| (h..=m)
+                                    .map(|i| (i, modulus(i, j)))
+                                    .fold((h, -1.0), |best, x| if x.1 > best.1 { x } else { best });
+        let row_max = |i: usize| (k..=last_column)
+                                 .map(|j| (j, modulus(i, j)))
+                                 .fold((k, -1.0), |best, x| if x.1 > best.1 { x } else { best });
+
+        let (row, column) = match self.pivoting {
+            PivotStrategy::FirstNonZero => {
+                ((h..=m).find(|&i| modulus(i, k) > tolerance)?, k)
+            }
+            PivotStrategy::Partial => (column_max(k).0, k),
+            PivotStrategy::Complete => {
+                let (column, (row, _)) = (k..=last_column)
+                                         .map(|j| (j, column_max(j)))
+                                         .fold((k, (h, -1.0)), |best, x| {
+                                             if x.1.1 > best.1.1 { x } else { best }
+                                         });
+                (row, column)
+            }
+            PivotStrategy::Rook => {
+                // starts from the first column that has a pivot candidate
+                let mut column = (k..=last_column).find(|&j| column_max(j).1 > tolerance)?;
+                let mut row = column_max(column).0;
+
+                loop {
+                    let (best_column, value) = row_max(row);
+
+                    if value <= modulus(row, column) {
+                        break;
+                    }
+
+                    column = best_column;
+                    let (best_row, value) = column_max(column);
+
+                    if value <= modulus(row, column) {
+                        break;
+                    }
+
+                    row = best_row;
+                }
+
+                (row, column)
+            }
+        };
+
+        if modulus(row, column) > tolerance {
+            Some((row, column))
+        } else {
+            None
+        }
+    }
+
+    fn echelon<T: Scalar>
+    (
+        &self,
+        matrix: &mut Matrix<T>,
+        last_column: usize,
+        tolerance: f64,
+        mut operations: Option<&mut RowOperations<T>>,
+    ) -> Vec<(usize, usize)>
+    {
+        // pivots are only searched up to last_column, so the right hand
+        // sides of an augmented matrix are never swapped into A
+        let mut h = 1;
+        let mut k = 1;
+
+        let (m, n) = matrix.dimension();
+        let mut leading_ones = vec![];
+
+        while h <= m && k <= last_column {
+            match self.find_pivot(matrix, (h, k), last_column, tolerance) {
+                // nothing left above the tolerance in the whole submatrix
+                None if self.pivoting.swaps_columns() => break,
+                None => k += 1,
+                Some((index, column)) => {
+                    if column != k {
+                        self.swap_columns(column, k, matrix);
+
+                        if let Some(operations) = operations.as_deref_mut() {
+                            operations.swap_columns(column, k);
+                        }
+                    }
 
-            match index {
-                0 => k += 1,
-                _ => {
                     self.swap_rows(index, h, matrix);
 
-                    if m == n {
-                        self.swap_rows(index, h, &mut to_inverse);
+                    if index != h {
+                        if let Some(operations) = operations.as_deref_mut() {
+                            operations.push(RowOperation::Swap(index, h));
+                        }
                     }
 
                     let pivot = matrix.element(h, k).unwrap();
@@ -627,11 +893,18 @@ impl Eliminator {
 
                     for a in (h + 1)..=m {
                         let factor = matrix.element(a, k).unwrap()
-                                     / pivot;
+                                     / pivot.clone();
 
-                        self.multiply_rows(a, h, factor, matrix);
+                        if factor.is_zero() {
+                            continue;
+                        }
 
-                        self.multiply_rows(a, h, factor, &mut to_inverse);
+                        self.multiply_rows(a, h, factor.clone(), matrix);
+                        matrix.change_element(a, k, T::zero());
+
+                        if let Some(operations) = operations.as_deref_mut() {
+                            operations.push(RowOperation::Subtract { target: a, source: h, factor });
+                        }
                     }
 
                     h += 1;
@@ -640,39 +913,78 @@ impl Eliminator {
             }
         }
 
-        (leading_ones, to_inverse)
+        leading_ones
+    }
+
+    pub fn rref<T: Scalar>(&self, matrix: &mut Matrix<T>) -> Vec<(usize, usize)> {
+        self.rref_tol(matrix, self.tolerance)
+    }
+
+    pub fn rref_tol<T: Scalar>(&self, matrix: &mut Matrix<T>, tolerance: f64) -> Vec<(usize, usize)> {
+        let n = matrix.dimension().1;
+        let pivots = self.echelon(matrix, n, tolerance, None);
+        self.reduce(matrix, &pivots, None);
+
+        pivots
     }
 
-    pub fn rref(&self, matrix: &mut Matrix) -> (Vec<(usize, usize)>, Matrix) {
-        let (mut leading_ones, mut to_inverse) = self.
-                            row_echelon_form(matrix);
-        let pivot_indexes = leading_ones.clone();
+    pub fn recorded_rref<T: Scalar>
+    (
+        &self,
+        matrix: &mut Matrix<T>,
+        tolerance: f64,
+    ) -> (Vec<(usize, usize)>, RowOperations<T>)
+    {
         let (m, n) = matrix.dimension();
+        let mut operations = RowOperations::new(m, n);
+        let pivots = self.echelon(matrix, n, tolerance, Some(&mut operations));
+        self.reduce(matrix, &pivots, Some(&mut operations));
 
-        for _ in 0..(leading_ones.len()) {
-            let most_right_index = leading_ones.pop();
-            let Some((i, j)) = most_right_index else { panic!("") };
+        (pivots, operations)
+    }
 
+    fn reduce<T: Scalar>
+    (
+        &self,
+        matrix: &mut Matrix<T>,
+        pivots: &[(usize, usize)],
+        mut operations: Option<&mut RowOperations<T>>,
+    )
+    {
+        // from echelon form to rref, clearing the columns of the pivots
+        // from the last one up and scaling the pivots to one
+        for &(i, j) in pivots.iter().rev() {
             let pivot = matrix.element(i, j).unwrap();
 
             for a in 1..i {
                 let element_above = matrix.element(a, j).unwrap();
-                let factor = element_above / pivot;
+                let factor = element_above / pivot.clone();
+
+                if factor.is_zero() {
+                    continue;
+                }
 
-                self.multiply_rows(a, i, factor, matrix);
+                self.multiply_rows(a, i, factor.clone(), matrix);
+                matrix.change_element(a, j, T::zero());
 
-                self.multiply_rows(a, i, factor, &mut to_inverse);
+                if let Some(operations) = operations.as_deref_mut() {
+                    operations.push(RowOperation::Subtract { target: a, source: i, factor });
+                }
             }
 
-            self.multiply_row_by_number(i, 1.0 / pivot, matrix);
+            let factor = T::one() / pivot;
 
-            self.multiply_row_by_number(i, 1.0 / pivot, &mut to_inverse);
-        }
+            self.multiply_row_by_number(i, factor.clone(), matrix);
+            matrix.change_element(i, j, T::one());
 
-        (pivot_indexes, to_inverse)
+            if let Some(operations) = operations.as_deref_mut() {
+                operations.push(RowOperation::Scale { row: i, factor });
+            }
+        }
     }
 }
 
+#[derive(Default)]
 pub struct Spaces {}
 
 impl Spaces {
@@ -680,91 +992,98 @@ impl Spaces {
         Self {}
     }
 
-    fn build_identity(&self, matrix: &mut Matrix) {
-
+    pub fn null_dimension<T: Scalar>(&self, matrix: &Matrix<T>) -> usize {
+        self.null_dimension_tol(matrix, 0.0)
     }
 
-    fn check_identity
-    (
-        &self, 
-        matrix: &mut Matrix,
-        pivot_indexes: &Vec<(usize, usize)>
-    )
-    {
-        let (bi, bj) = pivot_indexes[0];
-        let (bi, bj) = (bi as i64, bj as i64);
-
-        for (i, j) in pivot_indexes.iter() {
-            let (i, j) = (*i as i64, *j as i64);
-            if bi == i && bj == j {
-                continue;
-            }
+    pub fn null_dimension_tol<T: Scalar>(&self, matrix: &Matrix<T>, tolerance: f64) -> usize {
+        matrix.dimension().1 - self.column_dimension_tol(matrix, tolerance)
+    }
 
-            if (bj - j).abs() != (bi - i).abs() {
-                self.build_identity(matrix);
-            }
-        }
+    pub fn column_dimension<T: Scalar>(&self, matrix: &Matrix<T>) -> usize {
+        self.column_dimension_tol(matrix, 0.0)
     }
 
-    pub fn null_dimension(&self, matrix: &Matrix) -> usize {
-        matrix.dimension().1 - self.column_dimension(matrix)
+    pub fn column_dimension_tol<T: Scalar>(&self, matrix: &Matrix<T>, tolerance: f64) -> usize {
+        self.column_space_tol(matrix, tolerance).len()
     }
 
-    pub fn column_dimension(&self, matrix: &Matrix) -> usize {
-        self.column_space(matrix).len()
+    pub fn null_space<T: Scalar>(&self, matrix: &Matrix<T>) -> Vec<Vector<T>> {
+        self.null_space_tol(matrix, 0.0)
     }
 
-    pub fn null_space(&self, matrix: &Matrix) -> Vec<Vector> {
-        let mut eliminator = Eliminator::new();
-        let mut cloned_matrix = matrix.clone();
-        let (m, n) = cloned_matrix.dimension();
-        let (pivot_indexes, _) = eliminator.rref(&mut cloned_matrix);
+    pub fn null_space_tol<T: Scalar>(&self, matrix: &Matrix<T>, tolerance: f64) -> Vec<Vector<T>> {
+        // one basis vector per free column f of R = rref(A):
+        // x_f = 1, x_p = -R(k, f) for the pivot (k, p) of each row and zero
+        // elsewhere, so the pivot columns don't need to be contiguous
+        let eliminator = Eliminator::new();
+        let mut reduced = matrix.clone();
+        let n = reduced.dimension().1;
+        let pivot_indexes = eliminator.rref_tol(&mut reduced, tolerance);
 
-        let r = pivot_indexes.len(); // dimension of the column space
+        let pivot_columns = pivot_indexes.iter()
+                            .map(|&(_, j)| j)
+                            .collect::<Vec<usize>>();
 
-        if n - r == 0 {
-            return vec![];
-        }
-        
-        self.check_identity(&mut cloned_matrix, &pivot_indexes);
+        let mut basis = vec![];
 
-        let mut F = Matrix::zero(n, n - r);
+        for free in (1..=n).filter(|j| !pivot_columns.contains(j)) {
+            let mut vector = Vector::zero(n);
+            vector.change_element(free, T::one());
 
-        for j in (r+1)..=n {
-            for i in 1..=r {
-                let row_index = i;
-                let column_index = j - r;
+            for &(k, p) in pivot_indexes.iter() {
+                vector.change_element(p, -reduced.element(k, free).unwrap());
+            }
 
-                let element = -cloned_matrix.element(i, j).unwrap();
-                F.change_element(row_index, column_index, element);
-            } 
+            basis.push(vector);
         }
 
-        for i in (r+1)..=n {
-            for j in 1..=(n - r) {
-                if (i - r) == j {
-                    F.change_element(i, j, 1.0);
-                }
-            }
-        }
+        basis
+    }
 
-        F.columns
+    pub fn column_space<T: Scalar>(&self, matrix: &Matrix<T>) -> Vec<Vector<T>> {
+        self.column_space_tol(matrix, 0.0)
     }
 
-    pub fn column_space(&self, matrix: &Matrix) -> Vec<Vector> {
-        let mut eliminator = Eliminator::new();
+    pub fn column_space_tol<T: Scalar>(&self, matrix: &Matrix<T>, tolerance: f64) -> Vec<Vector<T>> {
+        let eliminator = Eliminator::new();
         let mut cloned = matrix.clone();
-        let (pivots, _) = eliminator.row_echelon_form(&mut cloned);
+        let pivots = eliminator.row_echelon_form_tol(&mut cloned, tolerance);
         let mut basis = vec![];
 
         for (_, j) in pivots {
             basis.push(matrix.get_column(j).unwrap());
-        }  
+        }
 
         basis
     }
+
+    pub fn row_space<T: Scalar>(&self, matrix: &Matrix<T>) -> Vec<Vector<T>> {
+        self.row_space_tol(matrix, 0.0)
+    }
+
+    pub fn row_space_tol<T: Scalar>(&self, matrix: &Matrix<T>, tolerance: f64) -> Vec<Vector<T>> {
+        // the nonzero rows of the rref
+        let eliminator = Eliminator::new();
+        let mut reduced = matrix.clone();
+        let pivots = eliminator.rref_tol(&mut reduced, tolerance);
+
+        pivots.iter()
+              .map(|&(i, _)| reduced.get_line(i).unwrap())
+              .collect()
+    }
+
+    pub fn left_null_space<T: Scalar>(&self, matrix: &Matrix<T>) -> Vec<Vector<T>> {
+        self.left_null_space_tol(matrix, 0.0)
+    }
+
+    pub fn left_null_space_tol<T: Scalar>(&self, matrix: &Matrix<T>, tolerance: f64) -> Vec<Vector<T>> {
+        // null space of A^T
+        self.null_space_tol(&matrix.transpose(), tolerance)
+    }
 }
 
+#[derive(Default)]
 pub struct Solver {}
 
 impl Solver {
@@ -772,7 +1091,7 @@ impl Solver {
         Self {}
     }
 
-    fn concatenate(&self, a: &Matrix, b: &Matrix) -> Matrix {
+    fn concatenate<T: Scalar>(&self, a: &Matrix<T>, b: &Matrix<T>) -> Matrix<T> {
         let (r1, c1) = a.dimension();
         let (r2, c2) = b.dimension();
 
@@ -795,38 +1114,100 @@ impl Solver {
         new
     }
 
-    pub fn solve(&self, matrix: &Matrix, b: &Vector) -> Vector {
+    fn check_system<T: Scalar>(&self, matrix: &Matrix<T>, b: &Vector<T>) -> Result<(), LinalgError> {
+        let m = matrix.dimension().0;
+
+        if m != b.size() {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (1, m),
+                found: b.dimension(),
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn solve<T: Scalar>(&self, matrix: &Matrix<T>, b: &Vector<T>) -> Vector<T> {
+        self.try_solve(matrix, b).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_solve<T: Scalar>
+    (
+        &self,
+        matrix: &Matrix<T>,
+        b: &Vector<T>
+    ) -> Result<Vector<T>, LinalgError>
+    {
+        // dispatches on the structure of the matrix and verifies the
+        // solution with a relative residual, see structured.rs
+        self.check_system(matrix, b)?;
+
+        self.structured_solve(matrix, b)
+    }
+
+    pub fn solve_with_pivoting<T: Scalar>
+    (
+        &self,
+        matrix: &Matrix<T>,
+        b: &Vector<T>,
+        eliminator: &Eliminator,
+    ) -> Vector<T>
+    {
+        self.try_solve_with_pivoting(matrix, b, eliminator)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_solve_with_pivoting<T: Scalar>
+    (
+        &self,
+        matrix: &Matrix<T>,
+        b: &Vector<T>,
+        eliminator: &Eliminator,
+    ) -> Result<Vector<T>, LinalgError>
+    {
+        // rref of [A | b] with the eliminator's pivoting and tolerance.
+        // free variables are set to zero, and the system has no solution
+        // when a row without pivot has a right hand side above the tolerance
         let (m, n) = matrix.dimension();
-        assert!(m == b.size());
+        self.check_system(matrix, b)?;
 
-        let b_as_matrix = Matrix::create_with_vectors(&[b.clone()]).transpose();
+        let b_as_matrix = Matrix::create_with_vectors(std::slice::from_ref(b)).transpose();
         let mut augmented_matrix = self.concatenate(matrix, &b_as_matrix);
 
-        let eliminator = Eliminator::new();
-        let (_, _) = eliminator.rref(&mut augmented_matrix);
+        let mut operations = RowOperations::new(m, n + 1);
+        let pivots = eliminator.echelon(&mut augmented_matrix, n, eliminator.tolerance,
+                                        Some(&mut operations));
+        eliminator.reduce(&mut augmented_matrix, &pivots, None);
 
-        let mut solution = Vector::zero(n);
-        let part_solution = augmented_matrix.get_column(n + 1).unwrap();
+        let rhs = augmented_matrix.get_column(n + 1).unwrap();
 
-        for i in 1..=n {
-            let element = part_solution.element(i).unwrap();
-            solution.change_element(i, element);
+        if ((pivots.len() + 1)..=m).any(|i| rhs.components[i - 1].modulus() > eliminator.tolerance) {
+            return Err(LinalgError::NoSolution);
         }
 
-        assert!(matrix * &solution == *b, "there is no solution");
+        let mut solution = Vector::zero(n);
+        let columns = operations.column_permutation();
+
+        for &(i, j) in pivots.iter() {
+            solution.components[columns[j - 1] - 1] = rhs.components[i - 1].clone();
+        }
 
-        solution
+        Ok(solution)
     }
 
     pub fn generic_solve(&self, matrix: &Matrix, b: &Vector) -> Vector {
+        self.try_generic_solve(matrix, b).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_generic_solve(&self, matrix: &Matrix, b: &Vector) -> Result<Vector, LinalgError> {
         let spaces = Spaces::new();
         let null_basis = spaces.null_space(matrix);
-        let particular_solution = self.solve(matrix, b);
+        let particular_solution = self.try_solve(matrix, b)?;
 
         let mut rng = rand::thread_rng();
         let mut solution = particular_solution;
 
-        if null_basis.len() > 0 {
+        if !null_basis.is_empty() {
             for vector in null_basis.iter() {
                 let random_number = rng.gen_range(-1.0..1.0);
 
@@ -834,58 +1215,85 @@ impl Solver {
             }
         }
 
-        solution
+        Ok(solution)
+    }
+
+    pub fn foward_substitution<T: Scalar>(&self, matrix: &Matrix<T>, b: &Vector<T>) -> Vector<T> {
+        self.try_foward_substitution(matrix, b)
+            .unwrap_or_else(|error| panic!("{}", error))
     }
 
-    pub fn foward_substitution(&self, matrix: &Matrix, b: &Vector) -> Vector {
+    pub fn try_foward_substitution<T: Scalar>
+    (
+        &self,
+        matrix: &Matrix<T>,
+        b: &Vector<T>
+    ) -> Result<Vector<T>, LinalgError>
+    {
         // apenas para matrizes triangulares inferiores positivas definidas
+        matrix.check_square()?;
+        self.check_system(matrix, b)?;
 
         let n = matrix.dimension().0;
         let mut x = Vector::zero(n);
 
         for i in 1..=n {
-            let mut sum = 0.0;
+            let mut sum = T::zero();
             let bi = b.element(i).unwrap();
             let aii = matrix.element(i, i).unwrap();
 
-            if aii == 0.0 {
-                panic!("bla bla bla");
+            if aii.is_zero() {
+                return Err(LinalgError::Singular);
             }
 
             for j in 1..=i-1 {
                 let aij = matrix.element(i, j).unwrap();
                 let xj = x.element(j).unwrap();
-                sum += aij * xj;
+                sum = sum + aij * xj;
             }
 
             x.change_element(i, (bi - sum) / aii);
         }
 
-        x
+        Ok(x)
     }
 
-    pub fn backward_substitution(&self, matrix: &Matrix, b: &Vector) -> Vector {
+    pub fn backward_substitution<T: Scalar>(&self, matrix: &Matrix<T>, b: &Vector<T>) -> Vector<T> {
+        self.try_backward_substitution(matrix, b)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_backward_substitution<T: Scalar>
+    (
+        &self,
+        matrix: &Matrix<T>,
+        b: &Vector<T>
+    ) -> Result<Vector<T>, LinalgError>
+    {
+        matrix.check_square()?;
+        self.check_system(matrix, b)?;
+
         let n = matrix.dimension().1;
         let mut x = Vector::zero(n);
         let mut i = n;
 
         loop {
-            if i <= 0 {
-                break x
+            if i == 0 {
+                break Ok(x)
             }
 
-            let mut sum = 0.0;
+            let mut sum = T::zero();
             let bi = b.element(i).unwrap();
             let uii = matrix.element(i, i).unwrap();
 
-            if uii == 0.0 {
-                panic!("bla bla bla");
+            if uii.is_zero() {
+                return Err(LinalgError::Singular);
             }
 
             for j in (i+1)..=n {
                 let uij = matrix.element(i, j).unwrap();
                 let xj = x.element(j).unwrap();
-                sum += uij * xj;
+                sum = sum + uij * xj;
             }
 
             x.change_element(i, (bi - sum) / uii);
@@ -893,164 +1301,579 @@ impl Solver {
         }
     }
 
-    pub fn jacobi
-    // apenas para matrizes quadradas
+    fn check_operator_system<A: LinearOperator>
     (
-        &self, 
-        matrix: &Matrix, 
-        b: &Vector, 
-        error: f64,
-        max_iter: usize,
-    ) -> Vector {
-        let (m, n) = matrix.dimension();
-        assert!(m == n);
+        &self,
+        operator: &A,
+        b: &Vector
+    ) -> Result<(), LinalgError>
+    {
+        let (m, n) = operator.dimension();
 
-        let mut x = Vector::zero(n);
-        let mut x_new = x.clone();
+        if m != n {
+            return Err(LinalgError::NotSquare { rows: m, columns: n });
+        }
 
-        for _ in 0..max_iter {
-            x_new.components.par_iter_mut()
-            .enumerate().for_each(|(i, x_new_i)| {
-                let mut sum = 0.0;
-                let i = i + 1;
+        if m != b.size() {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (1, m),
+                found: b.dimension(),
+            });
+        }
 
-                for j in 1..=n {
-                    if i != j {
-                        sum += matrix.element(i, j).unwrap() 
-                        * x.element(j).unwrap();
-                    }
-                }
+        Ok(())
+    }
 
-                let element = (b.element(i).unwrap() - sum) 
-                / matrix.element(i, i).unwrap();
-                *x_new_i = element;
-            });
+    fn operator_diagonal<A: LinearOperator>(&self, operator: &A) -> Result<Vector, LinalgError> {
+        let diagonal = operator.diagonal()
+                       .ok_or(LinalgError::Unsupported { operation: "diagonal" })?;
 
-            if x.components.iter().zip(&x_new.components)
-                .map(|(xi, xni)| (xi - xni).abs()).sum::<f64>() < error {
-                break;
-            }
+        if diagonal.components.contains(&0.0) {
+            return Err(LinalgError::Singular);
+        }
+
+        Ok(diagonal)
+    }
 
-            x = x_new.clone();
+    fn operator_rows<A: LinearOperator>
+    (
+        &self,
+        operator: &A
+    ) -> Result<Vec<Vec<(usize, f64)>>, LinalgError>
+    {
+        let n = operator.dimension().0;
+        let rows = (1..=n)
+                   .map(|i| operator.row(i))
+                   .collect::<Option<Vec<_>>>()
+                   .ok_or(LinalgError::Unsupported { operation: "row access" })?;
+
+        for (i, row) in rows.iter().enumerate() {
+            if !row.iter().any(|&(j, a)| j == i + 1 && a != 0.0) {
+                return Err(LinalgError::Singular);
+            }
         }
 
-        x
+        Ok(rows)
     }
 
-    pub fn gauss_seidel
+    pub fn jacobi<A: LinearOperator>
     // apenas para matrizes quadradas
     (
         &self,
-        matrix: &Matrix,
+        operator: &A,
         b: &Vector,
         error: f64,
         max_iter: usize,
-    ) -> Vector
+    ) -> IterativeResult
     {
-        let (m, n) = matrix.dimension();
-        assert!(m == n);
+        self.try_jacobi(operator, b, error, max_iter)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
 
-        let mut x = Vector::zero(n);
-        let mut x_new = x.clone();
+    pub fn try_jacobi<A: LinearOperator>
+    (
+        &self,
+        operator: &A,
+        b: &Vector,
+        error: f64,
+        max_iter: usize,
+    ) -> Result<IterativeResult, LinalgError>
+    {
+        // starts from zero and stops when |b - A x| <= error * |b|
+        let initial_guess = Vector::zero(b.size());
+        let criterion = StoppingCriterion::RelativeResidual(error);
 
-        for _ in 0..max_iter {
-            for i in 1..=n {
-                let mut sum = 0.0;
+        self.try_jacobi_with(operator, b, &initial_guess, &criterion, max_iter)
+    }
 
-                for j in 1..=n {
-                    if i != j {
-                        sum += matrix.element(i, j).unwrap()
-                               * x_new.element(j).unwrap();
-                    }
-                }
+    pub fn jacobi_with<A: LinearOperator>
+    (
+        &self,
+        operator: &A,
+        b: &Vector,
+        initial_guess: &Vector,
+        criterion: &StoppingCriterion,
+        max_iter: usize,
+    ) -> IterativeResult
+    {
+        self.try_jacobi_with(operator, b, initial_guess, criterion, max_iter)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
 
-                let element = (b.element(i).unwrap() - sum)
-                / matrix.element(i, i).unwrap();
-                x_new.change_element(i, element);
-            }
+    pub fn try_jacobi_with<A: LinearOperator>
+    (
+        &self,
+        operator: &A,
+        b: &Vector,
+        initial_guess: &Vector,
+        criterion: &StoppingCriterion,
+        max_iter: usize,
+    ) -> Result<IterativeResult, LinalgError>
+    {
+        self.check_operator_system(operator, b)?;
+        let diagonal = self.operator_diagonal(operator)?;
 
-            if x.components.iter().zip(&x_new.components)
-                .map(|(xi, xni)| (xi - xni).abs()).sum::<f64>() < error {
-                break;
-            }
+        // x_new = x + D^-1 (b - A x), which is row i solved for x_i
+        // with the other unknowns taken from x
+        let step = |x: &Vector, residual: &Vector| {
+            let x_new = x.components.par_iter()
+                        .zip(residual.components.par_iter())
+                        .zip(diagonal.components.par_iter())
+                        .map(|((xi, ri), di)| xi + ri / di)
+                        .collect::<Vec<f64>>();
 
-            x = x_new.clone();
-        }
+            Vector::create_with_vec(x_new)
+        };
 
-        x
+        let result = self.stationary_iterations(operator, b, initial_guess,
+                                                criterion, max_iter, step)?;
+
+        Ok(result.with_diagonal_dominance(operator.is_diagonally_dominant()))
     }
-}
 
-pub struct Decomposer {}
+    pub fn gauss_seidel<A: LinearOperator>
+    // apenas para matrizes quadradas
+    (
+        &self,
+        operator: &A,
+        b: &Vector,
+        error: f64,
+        max_iter: usize,
+    ) -> IterativeResult
+    {
+        self.sor(operator, b, 1.0, error, max_iter)
+    }
 
-pub struct Houlseholder {
-    R: Matrix,
-    vectors: Vec<Matrix>,
-}
+    pub fn try_gauss_seidel<A: LinearOperator>
+    (
+        &self,
+        operator: &A,
+        b: &Vector,
+        error: f64,
+        max_iter: usize,
+    ) -> Result<IterativeResult, LinalgError>
+    {
+        self.try_sor(operator, b, 1.0, error, max_iter)
+    }
 
-impl Houlseholder {
-    fn new(R: Matrix, vectors: Vec<Matrix>) -> Self {
-        Self {
-            R,
-            vectors,
-        }
+    pub fn gauss_seidel_with<A: LinearOperator>
+    (
+        &self,
+        operator: &A,
+        b: &Vector,
+        initial_guess: &Vector,
+        criterion: &StoppingCriterion,
+        max_iter: usize,
+    ) -> IterativeResult
+    {
+        self.sor_with(operator, b, 1.0, initial_guess, criterion, max_iter)
     }
 
-    pub fn get_q(self) -> Matrix {
-        let (m, n) = self.R.dimension();
-        let mut q_transpose = Matrix::identity(m);
+    pub fn try_gauss_seidel_with<A: LinearOperator>
+    (
+        &self,
+        operator: &A,
+        b: &Vector,
+        initial_guess: &Vector,
+        criterion: &StoppingCriterion,
+        max_iter: usize,
+    ) -> Result<IterativeResult, LinalgError>
+    {
+        self.try_sor_with(operator, b, 1.0, initial_guess, criterion, max_iter)
+    }
 
-        for k in 1..=min(n, m) {
-            let ort_proj = self.vectors[k-1].ortogonal_projector(true);
-            let I = Matrix::identity(m - k + 1);
-            let F = &I - &(2.0 * &ort_proj);
-            let qk = if k == 1 {
-                F
-            } else {
-                Matrix::create_with_diagonal(&[Matrix::identity(k-1),
-                     F])
-            };
+    pub fn sor<A: LinearOperator>
+    (
+        &self,
+        operator: &A,
+        b: &Vector,
+        omega: f64,
+        error: f64,
+        max_iter: usize,
+    ) -> IterativeResult
+    {
+        self.try_sor(operator, b, omega, error, max_iter)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
 
-            q_transpose = &qk * &q_transpose;
-        }
+    pub fn try_sor<A: LinearOperator>
+    (
+        &self,
+        operator: &A,
+        b: &Vector,
+        omega: f64,
+        error: f64,
+        max_iter: usize,
+    ) -> Result<IterativeResult, LinalgError>
+    {
+        // starts from zero and stops when |b - A x| <= error * |b|
+        let initial_guess = Vector::zero(b.size());
+        let criterion = StoppingCriterion::RelativeResidual(error);
 
-        q_transpose.transpose()
+        self.try_sor_with(operator, b, omega, &initial_guess, &criterion, max_iter)
     }
 
-    pub fn get_r(&self) -> Matrix {
-        self.R.clone()
+    pub fn sor_with<A: LinearOperator>
+    (
+        &self,
+        operator: &A,
+        b: &Vector,
+        omega: f64,
+        initial_guess: &Vector,
+        criterion: &StoppingCriterion,
+        max_iter: usize,
+    ) -> IterativeResult
+    {
+        self.try_sor_with(operator, b, omega, initial_guess, criterion, max_iter)
+            .unwrap_or_else(|error| panic!("{}", error))
     }
-}
 
-impl Decomposer {
-    pub fn new() -> Self {
-        Self {}
-    }
+    pub fn try_sor_with<A: LinearOperator>
+    (
+        &self,
+        operator: &A,
+        b: &Vector,
+        omega: f64,
+        initial_guess: &Vector,
+        criterion: &StoppingCriterion,
+        max_iter: usize,
+    ) -> Result<IterativeResult, LinalgError>
+    {
+        // successive over-relaxation, omega = 1 is gauss seidel.
+        // only 0 < omega < 2 can converge
+        if !(omega > 0.0 && omega < 2.0) {
+            return Err(LinalgError::InvalidParameter {
+                parameter: "omega",
+                requirement: "in (0, 2)",
+            });
+        }
 
-    pub fn gram_schmidt(&self, vectors: Vec<Vector>) -> Vec<Vector> {
-        let n = vectors.len();
-        let mut ort_vectors: Vec<Vector> = vec![];
+        self.check_operator_system(operator, b)?;
+        let rows = self.operator_rows(operator)?;
 
-        for i in 0..n {
-            let actual_vector = &vectors[i];
-            let mut ort_vec = vectors[i].clone();
+        // sweeps the rows in order, using the components already updated
+        let step = |x: &Vector, _: &Vector| {
+            let mut x_new = x.clone();
 
-            for j in 0..i {
-                if i == 0 {
-                    continue;
+            for (i, row) in rows.iter().enumerate() {
+                let mut sum = 0.0;
+                let mut diagonal = 0.0;
+
+                for &(j, a) in row.iter() {
+                    if j == i + 1 {
+                        diagonal = a;
+                    } else {
+                        sum += a * x_new.components[j - 1];
+                    }
                 }
 
-                let qj = &ort_vectors[j];
-                let dot = qj.dot_product(&actual_vector);
-                let proj = dot * qj;
-                ort_vec = &ort_vec - &proj;
+                let gauss_seidel = (b.components[i] - sum) / diagonal;
+                x_new.components[i] = (1.0 - omega) * x_new.components[i] + omega * gauss_seidel;
             }
 
-            ort_vec = ort_vec.normalize();
-            ort_vectors.push(ort_vec);
-        }
+            x_new
+        };
 
-        ort_vectors
+        let result = self.stationary_iterations(operator, b, initial_guess,
+                                                criterion, max_iter, step)?;
+
+        Ok(result.with_diagonal_dominance(operator.is_diagonally_dominant()))
+    }
+
+    fn stationary_iterations<A, F>
+    (
+        &self,
+        operator: &A,
+        b: &Vector,
+        initial_guess: &Vector,
+        criterion: &StoppingCriterion,
+        max_iter: usize,
+        mut step: F,
+    ) -> Result<IterativeResult, LinalgError>
+    where
+        A: LinearOperator,
+        F: FnMut(&Vector, &Vector) -> Vector,
+    {
+        // step receives the iterate and its residual b - A x
+        let b_norm = b.magnitude();
+        let mut x = initial_guess.clone();
+        let mut residual = b - &operator.try_apply(&x)?;
+        let mut history = vec![residual.magnitude()];
+
+        if criterion.is_met(0, history[0], b_norm, f64::INFINITY) {
+            return Ok(IterativeResult::new(x, 0, history, StopReason::Converged));
+        }
+
+        for k in 1..=max_iter {
+            let x_new = step(&x, &residual);
+            let step_norm = x.components.iter()
+                            .zip(x_new.components.iter())
+                            .map(|(xi, xni)| (xi - xni).abs())
+                            .sum::<f64>();
+
+            x = x_new;
+            residual = b - &operator.try_apply(&x)?;
+
+            let residual_norm = residual.magnitude();
+            history.push(residual_norm);
+
+            if !residual_norm.is_finite() {
+                return Ok(IterativeResult::new(x, k, history, StopReason::Diverged));
+            }
+
+            if criterion.is_met(k, residual_norm, b_norm, step_norm) {
+                return Ok(IterativeResult::new(x, k, history, StopReason::Converged));
+            }
+        }
+
+        Ok(IterativeResult::new(x, max_iter, history, StopReason::MaxIterations))
+    }
+}
+
+#[derive(Default)]
+pub struct Decomposer {}
+
+pub struct LuDecomposition<T = f64> {
+    // L (unit lower, diagonal not stored) and U share the same buffer
+    lu: Matrix<T>,
+    // row i of P A is the row permutation[i] of A (0-based)
+    permutation: Vec<usize>,
+    permutation_sign: f64,
+}
+
+impl<T: Scalar> LuDecomposition<T> {
+    fn new(matrix: &Matrix<T>) -> Self {
+        let n = matrix.dimension().0;
+        let mut lu = matrix.clone();
+        let mut permutation = (0..n).collect::<Vec<usize>>();
+        let mut permutation_sign = 1.0;
+
+        for k in 0..n {
+            let mut pivot_row = k;
+            let mut max = lu.data[k * n + k].modulus();
+
+            for i in (k + 1)..n {
+                let candidate = lu.data[i * n + k].modulus();
+
+                if candidate > max {
+                    max = candidate;
+                    pivot_row = i;
+                }
+            }
+
+            if pivot_row != k {
+                for j in 0..n {
+                    lu.data.swap(k * n + j, pivot_row * n + j);
+                }
+
+                permutation.swap(k, pivot_row);
+                permutation_sign = -permutation_sign;
+            }
+
+            let pivot = lu.data[k * n + k].clone();
+
+            if pivot.is_zero() {
+                continue;
+            }
+
+            for i in (k + 1)..n {
+                let factor = lu.data[i * n + k].clone() / pivot.clone();
+                lu.data[i * n + k] = factor.clone();
+
+                for j in (k + 1)..n {
+                    lu.data[i * n + j] = lu.data[i * n + j].clone()
+                                         - factor.clone() * lu.data[k * n + j].clone();
+                }
+            }
+        }
+
+        Self {
+            lu,
+            permutation,
+            permutation_sign,
+        }
+    }
+
+    pub fn l(&self) -> Matrix<T> {
+        let n = self.lu.dimension().0;
+        let mut l = Matrix::identity(n);
+
+        for i in 0..n {
+            for j in 0..i {
+                l.data[i * n + j] = self.lu.data[i * n + j].clone();
+            }
+        }
+
+        l
+    }
+
+    pub fn u(&self) -> Matrix<T> {
+        let n = self.lu.dimension().0;
+        let mut u = Matrix::zero(n, n);
+
+        for i in 0..n {
+            for j in i..n {
+                u.data[i * n + j] = self.lu.data[i * n + j].clone();
+            }
+        }
+
+        u
+    }
+
+    pub fn p(&self) -> Matrix<T> {
+        let n = self.lu.dimension().0;
+        let mut p = Matrix::zero(n, n);
+
+        for (i, &j) in self.permutation.iter().enumerate() {
+            p.data[i * n + j] = T::one();
+        }
+
+        p
+    }
+
+    pub fn permutation(&self) -> Vec<usize> {
+        self.permutation.iter().map(|i| i + 1).collect()
+    }
+
+    pub fn is_singular(&self) -> bool {
+        // a pivot that is zero relative to the largest one, |u_ii| <=
+        // n eps max |u_jj|. exact types only count true zeros
+        let n = self.lu.dimension().0;
+        let pivots = (0..n).map(|i| self.lu.data[i * n + i].modulus()).collect::<Vec<f64>>();
+        let largest = pivots.iter().copied().fold(0.0, f64::max);
+        let threshold = n as f64 * T::epsilon() * largest;
+
+        (0..n).any(|i| self.lu.data[i * n + i].is_zero() || pivots[i] <= threshold)
+    }
+
+    pub fn determinant(&self) -> T {
+        let n = self.lu.dimension().0;
+
+        (0..n).fold(T::from_f64(self.permutation_sign),
+                    |det, i| det * self.lu.data[i * n + i].clone())
+    }
+
+    pub fn solve(&self, b: &Vector<T>) -> Vector<T> {
+        self.try_solve(b).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_solve(&self, b: &Vector<T>) -> Result<Vector<T>, LinalgError> {
+        let n = self.lu.dimension().0;
+
+        if b.size() != n {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (1, n),
+                found: b.dimension(),
+            });
+        }
+
+        if self.is_singular() {
+            return Err(LinalgError::Singular);
+        }
+
+        let mut x = self.permutation.iter()
+                    .map(|&i| b.components[i].clone())
+                    .collect::<Vec<T>>();
+
+        // L y = P b
+        for i in 0..n {
+            let sum = (0..i).fold(T::zero(), |sum, j| {
+                sum + self.lu.data[i * n + j].clone() * x[j].clone()
+            });
+            x[i] = x[i].clone() - sum;
+        }
+
+        // U x = y
+        for i in (0..n).rev() {
+            let sum = ((i + 1)..n).fold(T::zero(), |sum, j| {
+                sum + self.lu.data[i * n + j].clone() * x[j].clone()
+            });
+            x[i] = (x[i].clone() - sum) / self.lu.data[i * n + i].clone();
+        }
+
+        Ok(Vector::create_with_vec(x))
+    }
+
+    pub fn solve_matrix(&self, b: &Matrix<T>) -> Matrix<T> {
+        self.try_solve_matrix(b).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_solve_matrix(&self, b: &Matrix<T>) -> Result<Matrix<T>, LinalgError> {
+        let n = self.lu.dimension().0;
+        let (m, k) = b.dimension();
+
+        if m != n {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (n, k),
+                found: (m, k),
+            });
+        }
+
+        let mut x = Matrix::zero(n, k);
+
+        for j in 1..=k {
+            let column = self.try_solve(&b.get_column(j).unwrap())?;
+            x.change_column(j, column);
+        }
+
+        Ok(x)
+    }
+
+    pub fn inverse(&self) -> Matrix<T> {
+        self.try_inverse().unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_inverse(&self) -> Result<Matrix<T>, LinalgError> {
+        let n = self.lu.dimension().0;
+
+        self.try_solve_matrix(&Matrix::identity(n))
+    }
+}
+
+impl LuDecomposition {
+    pub fn log_abs_determinant(&self) -> (f64, f64) {
+        // (0, -inf) for singular matrices
+        let n = self.lu.dimension().0;
+        let mut sign = self.permutation_sign;
+        let mut log = 0.0;
+
+        for i in 0..n {
+            let uii = self.lu.data[i * n + i];
+
+            if uii == 0.0 {
+                return (0.0, f64::NEG_INFINITY);
+            }
+
+            sign *= uii.signum();
+            log += uii.abs().ln();
+        }
+
+        (sign, log)
+    }
+}
+
+impl Decomposer {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn gram_schmidt(&self, vectors: Vec<Vector>) -> Vec<Vector> {
+        let mut ort_vectors: Vec<Vector> = vec![];
+
+        for actual_vector in vectors.iter() {
+            let mut ort_vec = actual_vector.clone();
+
+            for qj in ort_vectors.iter() {
+                let dot = qj.dot_product(actual_vector);
+                let proj = dot * qj;
+                ort_vec = &ort_vec - &proj;
+            }
+
+            ort_vec = ort_vec.normalize();
+            ort_vectors.push(ort_vec);
+        }
+
+        ort_vectors
     }
 
     pub fn classical_gs_naive(&self, matrix: &Matrix) -> (Matrix, Matrix) {
@@ -1099,37 +1922,6 @@ impl Decomposer {
         (Q, R)
     }
 
-    pub fn householder(&self, matrix: &Matrix) -> Houlseholder {
-        // muito mais ineficiente que classical_gs_naive
-        // parece que as funções que lidam com sub matrizes
-        // não são eficientes o bastante
-        // porem modified_gs nao lida com sub matrizes e mesmo
-        // assim tem uma eficiencia parecida com householder
-        // então é escolher entre estabilidade numerica ou eficiencia
-
-        let (m, n) = matrix.dimension();
-        let mut R = matrix.clone();
-        let mut vectors: Vec<Matrix> = vec![];
-
-        for k in 1..=min(n, m) {
-            let x = matrix.sub_vector((k, m), k);
-            let c = x.sign(1) * x.magnitude();
-            let mut vk = &(c * &Vector::canonical(1, m - k + 1)) + &x;
-            vk = vk.normalize();
-
-            let mut proj = R.sub_matrix((k, m), (k, n));
-            let vk_matrix = Matrix::create_with_vectors(&[vk]);
-            proj = 2.0 * &(&vk_matrix.transpose() * &(&(vk_matrix) * &proj));
-
-            R.subtract_by_sub((k, m), (k, n), &proj);
-            vectors.push(vk_matrix);
-        }
-
-        let k = Houlseholder::new(R, vectors);
-        k
-    }
-
-
     pub fn QR_nyoxon(&self, matrix: &Matrix) -> (Matrix, Matrix) {
         // tá errada
         let mut vectors = vec![];
@@ -1146,52 +1938,39 @@ impl Decomposer {
     }
 
     pub fn LU(&self, matrix: &Matrix) -> (Matrix, Matrix) {
-        // procurar um metodo mais eficiente talvez
-        let eliminator = Eliminator::new();
-        let mut mutable_matrix = matrix.clone();
-        let (_, E) = eliminator.row_echelon_form(&mut mutable_matrix);
+        // A = (P^T L) U, so the first factor is L with its rows permuted
+        let lu = self.lu(matrix);
 
-        (E.inverse(), mutable_matrix)
+        (&lu.p().transpose() * &lu.l(), lu.u())
     }
 
-    pub fn cholesky(&self, matrix: &Matrix) -> Matrix {
-        // para matrizes positivas semi definidas
-        // como nao tenho um algoritmo para verificar isso
-        // apenas verifica se a matriz é simetrica
-        assert!(*matrix == matrix.transpose());
+    pub fn lu<T: Scalar>(&self, matrix: &Matrix<T>) -> LuDecomposition<T> {
+        self.try_lu(matrix).unwrap_or_else(|error| panic!("{}", error))
+    }
 
-        let n = matrix.dimension().0;
-        let mut L = Matrix::zero(n, n);
+    pub fn try_lu<T: Scalar>
+    (
+        &self,
+        matrix: &Matrix<T>
+    ) -> Result<LuDecomposition<T>, LinalgError>
+    {
+        // P A = L U with partial pivoting
+        matrix.check_square()?;
 
-        for i in 1..=n {
-            for j in 1..=i {
-                let mut sum  = 0.0;
-                if i == j {
-                    let aii = matrix.element(i, i).unwrap();
-                    for k in 1..=i-1 {
-                        let lik = L.element(i, k).unwrap();
-                        sum += lik * lik;
-                    }
-                    let new_element = (aii - sum).sqrt();
-                    L.change_element(i, i, new_element);
-                } else {
-                    let aij = matrix.element(i, j).unwrap();
-                    let ljj = L.element(j, j).unwrap();
-                    for k in 1..=j-1 {
-                        let lik = L.element(i, k).unwrap();
-                        let ljk = L.element(j, k).unwrap();
-                        sum += lik * ljk;
-                    }
-                    let new_element = (aij - sum) / ljj;
-                    L.change_element(i, j, new_element); 
-                }
-            }
-        }
+        Ok(LuDecomposition::new(matrix))
+    }
+
+    pub fn cholesky(&self, matrix: &Matrix) -> Matrix {
+        self.try_cholesky(matrix).unwrap_or_else(|error| panic!("{}", error))
+    }
 
-        L
+    pub fn try_cholesky(&self, matrix: &Matrix) -> Result<Matrix, LinalgError> {
+        // exactly symmetric matrices only, see cholesky_factor
+        Ok(self.try_cholesky_factor(matrix, 0.0)?.l().clone())
     }
 }
 
+#[derive(Default)]
 pub struct LeastSquares {}
 
 impl LeastSquares {
@@ -1200,31 +1979,544 @@ impl LeastSquares {
     }
 
     pub fn via_cholesky(&self, matrix: &Matrix, b: &Vector) -> Vector {
+        self.try_via_cholesky(matrix, b).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_via_cholesky(&self, matrix: &Matrix, b: &Vector) -> Result<Vector, LinalgError> {
         // calculate the least squares solution
         // using the cholesky factorization of the matrix.
 
         let aTa = &matrix.transpose() * matrix;
-        let aTb = &matrix.transpose() * b;
+        let aTb = matrix.transpose().try_mul_vector(b)?;
 
         let decomposer = Decomposer::new();
         let solver = Solver::new();
-        let cholesky = decomposer.cholesky(&aTa);
-        let w = solver.foward_substitution(&cholesky, &aTb);
-        let x = solver.backward_substitution(&cholesky.transpose(), &w);
-
-        x
+        let cholesky = decomposer.try_cholesky(&aTa)?;
+        let w = solver.try_foward_substitution(&cholesky, &aTb)?;
+        solver.try_backward_substitution(&cholesky.transpose(), &w)
     }
 
     pub fn via_qr(&self, matrix: &Matrix, b: &Vector) -> Vector {
+        self.try_via_qr(matrix, b).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_via_qr(&self, matrix: &Matrix, b: &Vector) -> Result<Vector, LinalgError> {
         // calculate the least squares solution
-        // using its reduced qr factorization
+        // using its householder qr factorization
+
+        Ok(self.try_fit(matrix, b)?.solution().clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rational::Rational;
+
+    #[test]
+    fn inverse_of_numerically_singular_matrix_fails() {
+        let a = Matrix::new(&[&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0], &[7.0, 8.0, 9.0]]);
+
+        assert!(LuDecomposition::new(&a).is_singular());
+        assert_eq!(a.try_inverse(), Err(LinalgError::Singular));
+    }
+
+    #[test]
+    fn inverse_of_regular_matrix() {
+        let a = Matrix::new(&[&[4.0, 7.0], &[2.0, 6.0]]);
+        let product = &a * &a.inverse();
+        let identity = Matrix::<f64>::identity(2);
+
+        for (x, y) in product.data.iter().zip(identity.data.iter()) {
+            assert!((x - y).abs() < 1e-14);
+        }
+    }
+
+    #[test]
+    fn product_reports_both_operand_shapes() {
+        let a = Matrix::new(&[&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]]);
+        let b = Matrix::new(&[&[1.0, 2.0], &[3.0, 4.0]]);
+
+        assert_eq!(a.try_mul(&b), Err(LinalgError::DimensionMismatch { expected: (2, 3), found: (2, 2) }));
+        assert_eq!(b.try_mul(&a), Ok(Matrix::new(&[&[9.0, 12.0, 15.0], &[19.0, 26.0, 33.0]])));
+    }
+
+    fn max_difference(x: &Vector, y: &Vector) -> f64 {
+        x.components.iter()
+         .zip(y.components.iter())
+         .map(|(a, b)| (a - b).abs())
+         .fold(0.0, f64::max)
+    }
+
+    fn dominant_system() -> (Matrix, Vector, Vector) {
+        let a = Matrix::new(&[&[10.0, -1.0, 2.0, 0.0],
+                              &[-1.0, 11.0, -1.0, 3.0],
+                              &[2.0, -1.0, 10.0, -1.0],
+                              &[0.0, 3.0, -1.0, 8.0]]);
+        let x = Vector::new(&[1.0, 2.0, -1.0, 1.0]);
+        let b = &a * &x;
+
+        (a, b, x)
+    }
+
+    #[test]
+    fn stationary_methods_converge_on_dominant_systems() {
+        let (a, b, x) = dominant_system();
+        let solver = Solver::default();
+
+        for result in [solver.jacobi(&a, &b, 1e-10, 200),
+                       solver.gauss_seidel(&a, &b, 1e-10, 200),
+                       solver.sor(&a, &b, 1.1, 1e-10, 200)] {
+            assert_eq!(result.stop_reason(), StopReason::Converged);
+            assert_eq!(result.diagonally_dominant(), Some(true));
+            assert_eq!(result.residual_history().len(), result.iterations() + 1);
+            assert_eq!(result.residual_history()[0], b.magnitude());
+            assert!(result.residual_norm() <= 1e-10 * b.magnitude());
+            assert!(max_difference(&result.solution(), &x) < 1e-9);
+        }
+    }
+
+    #[test]
+    fn sor_with_unit_omega_is_gauss_seidel() {
+        let (a, b, _) = dominant_system();
+        let solver = Solver::default();
+        let gauss_seidel = solver.gauss_seidel(&a, &b, 1e-12, 100);
+        let sor = solver.sor(&a, &b, 1.0, 1e-12, 100);
+
+        assert_eq!(sor.iterations(), gauss_seidel.iterations());
+        assert_eq!(sor.residual_history(), gauss_seidel.residual_history());
+        assert_eq!(sor.solution(), gauss_seidel.solution());
+
+        // gauss seidel uses the updated components, so it needs fewer
+        // sweeps than jacobi
+        assert!(gauss_seidel.iterations() < solver.jacobi(&a, &b, 1e-12, 100).iterations());
+    }
+
+    #[test]
+    fn divergent_systems_report_max_iterations() {
+        // the jacobi and gauss seidel iteration matrices have spectral
+        // radius sqrt(6) and 6
+        let a = Matrix::new(&[&[1.0, 2.0], &[3.0, 1.0]]);
+        let b = Vector::new(&[3.0, 4.0]);
+        let solver = Solver::default();
+
+        for result in [solver.jacobi(&a, &b, 1e-8, 20),
+                       solver.gauss_seidel(&a, &b, 1e-8, 20)] {
+            assert_eq!(result.stop_reason(), StopReason::MaxIterations);
+            assert!(!result.converged());
+            assert_eq!(result.diagonally_dominant(), Some(false));
+            assert_eq!(result.iterations(), 20);
+            assert_eq!(result.residual_history().len(), 21);
+            assert!(result.residual_norm() > b.magnitude());
+        }
 
+        // the growth eventually overflows
+        let result = solver.gauss_seidel(&a, &b, 1e-8, 1000);
+
+        assert_eq!(result.stop_reason(), StopReason::Diverged);
+        assert_eq!(result.residual_history().len(), result.iterations() + 1);
+    }
+
+    #[test]
+    fn stopping_criteria() {
+        let (a, b, _) = dominant_system();
+        let solver = Solver::default();
+        let zero = Vector::zero(4);
+
+        let custom = StoppingCriterion::Custom(Box::new(|iteration, _, _| iteration == 3));
+        let result = solver.jacobi_with(&a, &b, &zero, &custom, 100);
+
+        assert_eq!(result.stop_reason(), StopReason::Converged);
+        assert_eq!(result.iterations(), 3);
+        assert_eq!(result.residual_history().len(), 4);
+
+        // an absolute step criterion that no step can meet
+        let step = StoppingCriterion::AbsoluteStep(0.0);
+        let result = solver.gauss_seidel_with(&a, &b, &zero, &step, 15);
+
+        assert_eq!(result.stop_reason(), StopReason::MaxIterations);
+        assert_eq!(result.residual_history().len(), 16);
+
+        // an initial guess that already solves the system takes no iteration
+        let exact = solver.gauss_seidel(&a, &b, 1e-14, 100).solution();
+        let relative = StoppingCriterion::RelativeResidual(1e-12);
+        let result = solver.sor_with(&a, &b, 1.5, &exact, &relative, 100);
+
+        assert_eq!(result.iterations(), 0);
+        assert_eq!(result.residual_history().len(), 1);
+    }
+
+    #[test]
+    fn stationary_method_errors() {
+        let (a, b, _) = dominant_system();
+        let solver = Solver::default();
+        let zero_diagonal = Matrix::new(&[&[0.0, 1.0], &[1.0, 0.0]]);
+        let two = Vector::new(&[1.0, 1.0]);
+
+        assert!(matches!(solver.try_sor(&a, &b, 2.0, 1e-8, 10),
+                         Err(LinalgError::InvalidParameter { parameter: "omega", .. })));
+        assert!(matches!(solver.try_sor(&a, &b, f64::NAN, 1e-8, 10),
+                         Err(LinalgError::InvalidParameter { .. })));
+        assert!(matches!(solver.try_jacobi(&a, &two, 1e-8, 10),
+                         Err(LinalgError::DimensionMismatch { .. })));
+        assert_eq!(solver.try_jacobi(&zero_diagonal, &two, 1e-8, 10).err(), Some(LinalgError::Singular));
+        assert_eq!(solver.try_gauss_seidel(&zero_diagonal, &two, 1e-8, 10).err(), Some(LinalgError::Singular));
+        assert_eq!(solver.try_jacobi(&Matrix::new(&[&[1.0, 2.0]]), &Vector::new(&[1.0]), 1e-8, 10).err(),
+                   Some(LinalgError::NotSquare { rows: 1, columns: 2 }));
+    }
+
+    fn max_entry_difference(a: &Matrix, b: &Matrix) -> f64 {
+        a.data.iter()
+         .zip(b.data.iter())
+         .map(|(x, y)| (x - y).abs())
+         .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn lu_factors_the_row_permuted_matrix() {
         let decomposer = Decomposer::new();
-        let solver = Solver::new();
-        let (Q, R) = decomposer.classical_gs_naive(&matrix);
-        let qTb = &Q.transpose() * b;
-        let x = solver.backward_substitution(&R, &qTb);
+        let a = Matrix::new(&[&[0.0, 2.0, 1.0, 4.0],
+                              &[1.0, 1.0, 1.0, 1.0],
+                              &[6.0, -3.0, 2.0, 0.5],
+                              &[2.0, 8.0, -1.0, 3.0]]);
+        let lu = decomposer.lu(&a);
+        let (l, u) = (lu.l(), lu.u());
+
+        assert!(max_entry_difference(&(&lu.p() * &a), &(&l * &u)) < 1e-14);
+
+        // unit lower and upper triangular, with multipliers of at most 1
+        for i in 1..=4 {
+            assert_eq!(l.element(i, i), Some(1.0));
+
+            for j in (i + 1)..=4 {
+                assert_eq!(l.element(i, j), Some(0.0));
+                assert_eq!(u.element(j, i), Some(0.0));
+                assert!(l.element(j, i).unwrap().abs() <= 1.0);
+            }
+        }
+
+        // row 1 of P A is the 6 of the third row
+        assert_eq!(lu.permutation()[0], 3);
+
+        let (permuted_l, u) = decomposer.LU(&a);
+
+        assert!(max_entry_difference(&(&permuted_l * &u), &a) < 1e-14);
+    }
+
+    #[test]
+    fn determinant_sign_follows_the_row_swaps() {
+        // one swap
+        let a = Matrix::new(&[&[0.0, 1.0], &[1.0, 0.0]]);
+
+        assert_eq!(a.determinant(), -1.0);
+
+        // det = -3, found after swapping the 2 of the last row to the top
+        let b: Matrix = Matrix::new(&[&[0.0, 2.0, 1.0], &[1.0, 1.0, 1.0], &[2.0, 1.0, 3.0]]);
+        let lu = Decomposer::new().lu(&b);
+
+        assert!((b.determinant() + 3.0).abs() < 1e-14);
+        assert_eq!(lu.permutation()[0], 3);
+
+        // two swaps of a cyclic permutation keep the sign
+        let cycle = Matrix::new(&[&[0.0, 0.0, 2.0], &[3.0, 0.0, 0.0], &[0.0, 4.0, 0.0]]);
+
+        assert_eq!(cycle.determinant(), 24.0);
+        assert_eq!(Matrix::new(&[&[1.0, 2.0], &[2.0, 4.0]]).determinant(), 0.0);
+    }
+
+    #[test]
+    fn lu_solves_systems_with_a_zero_leading_entry() {
+        let a = Matrix::new(&[&[0.0, 2.0, 1.0], &[1.0, 1.0, 1.0], &[2.0, 1.0, 3.0]]);
+        let x = Vector::new(&[1.0, -1.0, 2.0]);
+        let b = &a * &x;
+        let lu = Decomposer::new().lu(&a);
+
+        assert!(max_difference(&lu.try_solve(&b).unwrap(), &x) < 1e-14);
+        assert!(max_difference(&Solver::default().solve(&a, &b), &x) < 1e-14);
+        assert!(max_entry_difference(&(&a * &lu.inverse()), &Matrix::identity(3)) < 1e-14);
+
+        assert!(matches!(lu.try_solve(&Vector::new(&[1.0, 2.0])),
+                         Err(LinalgError::DimensionMismatch { .. })));
+
+        let singular = Decomposer::new().lu(&Matrix::new(&[&[0.0, 1.0], &[0.0, 2.0]]));
+
+        assert_eq!(singular.try_solve(&Vector::new(&[1.0, 2.0])), Err(LinalgError::Singular));
+        assert_eq!(Decomposer::new().try_lu(&Matrix::new(&[&[1.0, 2.0]])).err(),
+                   Some(LinalgError::NotSquare { rows: 1, columns: 2 }));
+    }
+
+    #[test]
+    fn log_abs_determinant_does_not_overflow() {
+        let a = &Matrix::identity(200) * 200.0;
+
+        assert_eq!(a.determinant(), f64::INFINITY);
+
+        let (sign, log) = a.log_abs_determinant();
+
+        assert_eq!(sign, 1.0);
+        assert!(log.is_finite());
+        assert!((log - 200.0 * 200.0_f64.ln()).abs() < 1e-10);
 
-        x
+        // and the tiny counterpart doesn't underflow to a singular matrix
+        let (sign, log) = (&Matrix::identity(200) * 0.005).log_abs_determinant();
+
+        assert_eq!(sign, 1.0);
+        assert!((log + 200.0 * 200.0_f64.ln()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn log_abs_determinant_sign() {
+        // det = -3 with a row swap and a negative pivot involved
+        let a = Matrix::new(&[&[0.0, 2.0, 1.0], &[1.0, 1.0, 1.0], &[2.0, 1.0, 3.0]]);
+        let (sign, log) = a.log_abs_determinant();
+
+        assert_eq!(sign, -1.0);
+        assert!((log - 3.0_f64.ln()).abs() < 1e-14);
+
+        // negative diagonal entries without swaps: det = -2 * 3 * -4 * -1
+        let d = Matrix::new(&[&[-2.0, 0.0, 0.0, 0.0],
+                              &[0.0, 3.0, 0.0, 0.0],
+                              &[0.0, 0.0, -4.0, 0.0],
+                              &[0.0, 0.0, 0.0, -1.0]]);
+        let (sign, log) = d.log_abs_determinant();
+
+        assert_eq!(sign, -1.0);
+        assert!((log - 24.0_f64.ln()).abs() < 1e-14);
+
+        let singular = Matrix::new(&[&[1.0, 2.0], &[2.0, 4.0]]);
+
+        assert_eq!(singular.log_abs_determinant(), (0.0, f64::NEG_INFINITY));
+        assert_eq!(Matrix::new(&[&[1.0, 2.0]]).try_log_abs_determinant(),
+                   Err(LinalgError::NotSquare { rows: 1, columns: 2 }));
+    }
+
+    #[test]
+    fn spaces_with_non_contiguous_pivot_columns() {
+        // pivots in columns 1, 3 and 6, the third row is the sum of the
+        // others scaled by 2 and 3
+        let a = Matrix::new(&[&[1.0, 2.0, 0.0, 1.0, 3.0, 0.0],
+                              &[0.0, 0.0, 1.0, 4.0, -1.0, 0.0],
+                              &[2.0, 4.0, 3.0, 14.0, 3.0, 0.0],
+                              &[1.0, 2.0, 1.0, 5.0, 2.0, 7.0]]);
+        let (m, n) = a.dimension();
+        let spaces = Spaces::new();
+        let tolerance = 1e-12;
+
+        let null = spaces.null_space_tol(&a, tolerance);
+        let left_null = spaces.left_null_space_tol(&a, tolerance);
+        let columns = spaces.column_space_tol(&a, tolerance);
+        let rows = spaces.row_space_tol(&a, tolerance);
+
+        assert_eq!(columns.len(), 3);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(columns.len() + null.len(), n);
+        assert_eq!(rows.len() + left_null.len(), m);
+        assert_eq!(spaces.null_dimension_tol(&a, tolerance), null.len());
+        assert_eq!(spaces.column_dimension_tol(&a, tolerance), columns.len());
+
+        // the column space is spanned by the pivot columns of A
+        for (vector, j) in columns.iter().zip([1, 3, 6]) {
+            assert_eq!(vector, &a.get_column(j).unwrap());
+        }
+
+        let zero = |size: usize| Vector::zero(size);
+
+        for x in null.iter() {
+            assert!(max_difference(&(&a * x), &zero(m)) < 1e-12);
+
+            for row in rows.iter() {
+                assert!(row.dot_product(x).abs() < 1e-12);
+            }
+        }
+
+        for y in left_null.iter() {
+            assert!(max_difference(&(&a.transpose() * y), &zero(n)) < 1e-12);
+        }
+
+        // the basis vectors are independent, one per free column 2, 4 and 5
+        let basis = Matrix::create_with_vectors(&null);
+
+        assert_eq!(spaces.column_dimension_tol(&basis, tolerance), 3);
+
+        for (x, free) in null.iter().zip([2, 4, 5]) {
+            for j in [2, 4, 5] {
+                assert_eq!(x.components[j - 1], if j == free { 1.0 } else { 0.0 });
+            }
+        }
+    }
+
+    #[test]
+    fn spaces_of_full_rank_and_zero_matrices() {
+        let spaces = Spaces::new();
+        let zero: Matrix = Matrix::zero(2, 3);
+        let wide = Matrix::new(&[&[1.0, 0.0, 2.0], &[0.0, 1.0, 3.0]]);
+
+        assert_eq!(spaces.null_space(&zero).len(), 3);
+        assert_eq!(spaces.left_null_space(&zero).len(), 2);
+        assert!(spaces.column_space(&zero).is_empty());
+        assert!(spaces.row_space(&zero).is_empty());
+
+        assert_eq!(spaces.null_space(&wide), vec![Vector::new(&[-2.0, -3.0, 1.0])]);
+        assert!(spaces.left_null_space(&wide).is_empty());
+        assert_eq!(spaces.column_dimension(&wide.transpose()), 2);
+    }
+
+    fn hilbert(n: usize) -> Matrix<Rational> {
+        let rows = (1..=n)
+                   .map(|i| (1..=n).map(|j| Rational::new(1, (i + j - 1) as i64)).collect())
+                   .collect::<Vec<Vec<Rational>>>();
+        let rows = rows.iter().map(|row| row.as_slice()).collect::<Vec<&[Rational]>>();
+
+        Matrix::new(&rows)
+    }
+
+    fn binomial(n: i64, k: i64) -> i64 {
+        (1..=k).fold(1, |product, i| product * (n - k + i) / i)
+    }
+
+    #[test]
+    fn rational_hilbert_inverse_is_exact() {
+        let n = 6;
+        let h = hilbert(n);
+        let inverse = h.inverse();
+
+        // the closed form of the inverse of the hilbert matrix
+        for i in 1..=n as i64 {
+            for j in 1..=n as i64 {
+                let sign = if (i + j) % 2 == 0 { 1 } else { -1 };
+                let entry = sign * (i + j - 1)
+                            * binomial(n as i64 + i - 1, n as i64 - j)
+                            * binomial(n as i64 + j - 1, n as i64 - i)
+                            * binomial(i + j - 2, i - 1).pow(2);
+                let value = inverse.element(i as usize, j as usize).unwrap();
+
+                assert!(value.is_integer());
+                assert_eq!(value, Rational::from_integer(entry));
+            }
+        }
+
+        assert_eq!(&h * &inverse, Matrix::identity(n));
+        assert_eq!(hilbert(4).determinant(), Rational::new(1, 6048000));
+        assert_eq!(hilbert(5).determinant(), Rational::new(1, 266716800000));
+    }
+
+    #[test]
+    fn rational_elimination_is_exact() {
+        let r = |p: i64| Rational::from_integer(p);
+        let a = Matrix::new(&[&[r(1), r(2), r(3)], &[r(4), r(5), r(6)], &[r(7), r(8), r(9)]]);
+
+        // exactly singular, which floating point only gets to within rounding
+        assert_eq!(a.determinant(), r(0));
+        assert_eq!(a.try_inverse(), Err(LinalgError::Singular));
+
+        let mut reduced = a.clone();
+        let pivots = Eliminator::new().rref(&mut reduced);
+
+        assert_eq!(pivots, vec![(1, 1), (2, 2)]);
+        assert_eq!(reduced, Matrix::new(&[&[r(1), r(0), r(-1)], &[r(0), r(1), r(2)], &[r(0), r(0), r(0)]]));
+
+        let null = Spaces::new().null_space(&a);
+
+        assert_eq!(null, vec![Vector::new(&[r(1), r(-2), r(1)])]);
+        assert_eq!(&a * &null[0], Vector::zero(3));
+
+        // thirds stay exact through the elimination
+        let b = Matrix::new(&[&[r(3), r(1)], &[r(1), r(3)]]);
+        let expected = Matrix::new(&[&[Rational::new(3, 8), Rational::new(-1, 8)],
+                                     &[Rational::new(-1, 8), Rational::new(3, 8)]]);
+
+        assert_eq!(b.inverse(), expected);
+        assert_eq!(b.determinant(), r(8));
+    }
+
+    fn first_pivot(strategy: PivotStrategy, matrix: &Matrix) -> (Vec<f64>, Vec<usize>) {
+        // first row of the echelon form and the column permutation
+        let mut echelon = matrix.clone();
+        let eliminator = Eliminator::with_pivoting(strategy, 0.0);
+        let (_, operations) = eliminator.recorded_row_echelon_form(&mut echelon, 0.0);
+
+        (echelon.row_slice(1).to_vec(), operations.column_permutation().to_vec())
+    }
+
+    #[test]
+    fn pivot_strategies_pick_different_pivots() {
+        // partial takes the 2 of the first column, complete the 10
+        let a = Matrix::new(&[&[1.0, 10.0], &[2.0, 1.0]]);
+
+        assert_eq!(first_pivot(PivotStrategy::FirstNonZero, &a), (vec![1.0, 10.0], vec![1, 2]));
+        assert_eq!(first_pivot(PivotStrategy::Partial, &a), (vec![2.0, 1.0], vec![1, 2]));
+        assert_eq!(first_pivot(PivotStrategy::Complete, &a), (vec![10.0, 1.0], vec![2, 1]));
+        // 2 is the largest of its row and of its column, so rook stops there
+        assert_eq!(first_pivot(PivotStrategy::Rook, &a), (vec![2.0, 1.0], vec![1, 2]));
+
+        // here the 3 of partial pivoting is beaten by the 10 in its row
+        let b = Matrix::new(&[&[2.0, 1.0], &[3.0, 10.0]]);
+
+        assert_eq!(first_pivot(PivotStrategy::Partial, &b), (vec![3.0, 10.0], vec![1, 2]));
+        assert_eq!(first_pivot(PivotStrategy::Rook, &b), (vec![10.0, 3.0], vec![2, 1]));
+        assert_eq!(first_pivot(PivotStrategy::Complete, &b), (vec![10.0, 3.0], vec![2, 1]));
+    }
+
+    #[test]
+    fn column_swapping_strategies_solve_in_the_original_order() {
+        let solver = Solver::default();
+        let a = Matrix::new(&[&[1.0, 2.0, 8.0], &[4.0, -1.0, 1.0], &[2.0, 9.0, 3.0]]);
+        let x = Vector::new(&[1.0, -2.0, 3.0]);
+        let b = &a * &x;
+
+        // rank 2 with a zero free variable: x3 = 0 after the swaps
+        let singular = Matrix::new(&[&[1.0, 2.0, 3.0], &[2.0, 4.0, 7.0], &[1.0, 2.0, 4.0]]);
+        let c = &singular * &Vector::new(&[1.0, 1.0, 1.0]);
+
+        for strategy in [PivotStrategy::FirstNonZero, PivotStrategy::Partial,
+                         PivotStrategy::Complete, PivotStrategy::Rook] {
+            let eliminator = Eliminator::with_pivoting(strategy, 1e-12);
+            let solution = solver.solve_with_pivoting(&a, &b, &eliminator);
+
+            assert!(max_difference(&solution, &x) < 1e-12);
+
+            let solution = solver.solve_with_pivoting(&singular, &c, &eliminator);
+
+            assert!(max_difference(&(&singular * &solution), &c) < 1e-12);
+            assert_eq!(solution.components.iter().filter(|&&x| x == 0.0).count(), 1);
+        }
+    }
+
+    #[test]
+    fn tolerance_decides_the_rank_of_nearly_singular_matrices() {
+        let solver = Solver::default();
+        let a = Matrix::new(&[&[1.0, 1.0], &[1.0, 1.0 + 1e-10]]);
+
+        for strategy in [PivotStrategy::Partial, PivotStrategy::Complete, PivotStrategy::Rook] {
+            let exact = Eliminator::with_pivoting(strategy, 0.0);
+            let loose = Eliminator::with_pivoting(strategy, 1e-8);
+
+            assert_eq!(exact.row_echelon_form(&mut a.clone()).len(), 2);
+            assert_eq!(loose.row_echelon_form(&mut a.clone()).len(), 1);
+            assert_eq!(loose.tolerance(), 1e-8);
+            assert_eq!(loose.pivoting(), strategy);
+
+            // a right hand side off the range by less than the tolerance is
+            // consistent, and one far off it has no solution
+            let b = Vector::new(&[2.0, 3.0]);
+            let nearly = Vector::new(&[2.0, 2.0 + 1e-10]);
+
+            assert!(solver.solve_with_pivoting(&a, &b, &exact).magnitude() > 1e9);
+            assert_eq!(solver.try_solve_with_pivoting(&a, &b, &loose), Err(LinalgError::NoSolution));
+
+            let solution = solver.solve_with_pivoting(&a, &nearly, &loose);
+
+            assert!(max_difference(&(&a * &solution), &nearly) < 1e-8);
+        }
+
+        // first non zero skips a tiny leading entry only with the tolerance
+        let tiny = Matrix::new(&[&[1e-12, 1.0], &[1.0, 1.0]]);
+
+        assert_eq!(first_pivot(PivotStrategy::FirstNonZero, &tiny).0, vec![1e-12, 1.0]);
+
+        let mut echelon = tiny.clone();
+        Eliminator::with_pivoting(PivotStrategy::FirstNonZero, 1e-10).row_echelon_form(&mut echelon);
+
+        assert_eq!(echelon.row_slice(1), &[1.0, 1.0]);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,442 @@
+use crate::*;
+
+// anything that can be applied to a vector. iterative solvers only need
+// `apply`, so stencils and implicit operators don't have to be stored
+// as matrices. the optional methods return None when not available
+pub trait LinearOperator {
+    fn dimension(&self) -> (usize, usize);
+
+    fn apply(&self, x: &Vector) -> Vector;
+
+    fn try_apply(&self, x: &Vector) -> Result<Vector, LinalgError> {
+        let (_, n) = self.dimension();
+
+        if x.size() != n {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (1, n),
+                found: x.dimension(),
+            });
+        }
+
+        Ok(self.apply(x))
+    }
+
+    // A^T x
+    fn transpose_apply(&self, x: &Vector) -> Option<Vector> {
+        None
+    }
+
+    fn try_transpose_apply(&self, x: &Vector) -> Result<Option<Vector>, LinalgError> {
+        let (m, _) = self.dimension();
+
+        if x.size() != m {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (1, m),
+                found: x.dimension(),
+            });
+        }
+
+        Ok(self.transpose_apply(x))
+    }
+
+    fn diagonal(&self) -> Option<Vector> {
+        None
+    }
+
+    // nonzero entries (column, value) of the row, 1-based. panics
+    // when i is not a row of the operator
+    fn row(&self, i: usize) -> Option<Vec<(usize, f64)>> {
+        None
+    }
+
+    // strict row diagonal dominance, |a_ii| > sum |a_ij| for j != i.
+    // None when the operator has no row access
+    fn is_diagonally_dominant(&self) -> Option<bool> {
+        let (m, _) = self.dimension();
+
+        for i in 1..=m {
+            let (mut diagonal, mut others) = (0.0, 0.0);
+
+            for (j, a) in self.row(i)? {
+                if j == i {
+                    diagonal = a.abs();
+                } else {
+                    others += a.abs();
+                }
+            }
+
+            if diagonal <= others {
+                return Some(false);
+            }
+        }
+
+        Some(true)
+    }
+}
+
+// apply and transpose_apply panic like the matrix product when x
+// doesn't have `size` components
+pub(crate) fn check_operand(x: &Vector, size: usize) {
+    if x.size() != size {
+        panic!("{}", LinalgError::DimensionMismatch {
+            expected: (1, size),
+            found: x.dimension(),
+        });
+    }
+}
+
+pub(crate) fn check_row(i: usize, (rows, columns): (usize, usize)) {
+    if i == 0 || i > rows {
+        panic!("{}", LinalgError::IndexOutOfBounds {
+            index: (i, 1),
+            dimension: (rows, columns),
+        });
+    }
+}
+
+impl LinearOperator for Matrix {
+    fn dimension(&self) -> (usize, usize) {
+        Matrix::dimension(self)
+    }
+
+    fn apply(&self, x: &Vector) -> Vector {
+        self * x
+    }
+
+    fn transpose_apply(&self, x: &Vector) -> Option<Vector> {
+        let (m, n) = Matrix::dimension(self);
+        check_operand(x, m);
+
+        let mut result = vec![0.0; n];
+
+        for (row, xi) in self.data.chunks_exact(n).zip(x.components.iter()) {
+            for (r, a) in result.iter_mut().zip(row.iter()) {
+                *r += a * xi;
+            }
+        }
+
+        Some(Vector::create_with_vec(result))
+    }
+
+    fn diagonal(&self) -> Option<Vector> {
+        let (m, n) = Matrix::dimension(self);
+        let diagonal = (0..min(m, n))
+                       .map(|i| self.data[i * n + i])
+                       .collect::<Vec<f64>>();
+
+        Some(Vector::create_with_vec(diagonal))
+    }
+
+    fn row(&self, i: usize) -> Option<Vec<(usize, f64)>> {
+        check_row(i, Matrix::dimension(self));
+
+        let entries = self.row_slice(i).iter()
+                      .enumerate()
+                      .filter(|(_, &a)| a != 0.0)
+                      .map(|(j, &a)| (j + 1, a))
+                      .collect();
+
+        Some(entries)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiagonalOperator {
+    diagonal: Vector,
+}
+
+impl DiagonalOperator {
+    pub fn new(diagonal: Vector) -> Self {
+        Self {
+            diagonal,
+        }
+    }
+}
+
+impl LinearOperator for DiagonalOperator {
+    fn dimension(&self) -> (usize, usize) {
+        (self.diagonal.size(), self.diagonal.size())
+    }
+
+    fn apply(&self, x: &Vector) -> Vector {
+        check_operand(x, self.diagonal.size());
+
+        let result = self.diagonal.components.iter()
+                     .zip(x.components.iter())
+                     .map(|(d, xi)| d * xi)
+                     .collect::<Vec<f64>>();
+
+        Vector::create_with_vec(result)
+    }
+
+    fn transpose_apply(&self, x: &Vector) -> Option<Vector> {
+        Some(self.apply(x))
+    }
+
+    fn diagonal(&self) -> Option<Vector> {
+        Some(self.diagonal.clone())
+    }
+
+    fn row(&self, i: usize) -> Option<Vec<(usize, f64)>> {
+        check_row(i, LinearOperator::dimension(self));
+
+        Some(vec![(i, self.diagonal.components[i - 1])])
+    }
+}
+
+// square matrix with `lower` diagonals below the main one and `upper`
+// above it. band k (0 = lowest) holds the entries with j - i = k - lower,
+// stored by row, so element (i, j) lives at data[k * size + i - 1]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BandedOperator {
+    size: usize,
+    lower: usize,
+    upper: usize,
+    data: Vec<f64>,
+}
+
+impl BandedOperator {
+    pub fn new(size: usize, lower: usize, upper: usize) -> Self {
+        Self {
+            size,
+            lower,
+            upper,
+            data: vec![0.0; (lower + upper + 1) * size],
+        }
+    }
+
+    fn band_index(&self, row: usize, column: usize) -> Option<usize> {
+        if row == 0 || column == 0 || row > self.size || column > self.size
+            || column + self.lower < row || row + self.upper < column {
+            return None;
+        }
+
+        let k = column + self.lower - row;
+
+        Some(k * self.size + row - 1)
+    }
+
+    pub fn element(&self, row: usize, column: usize) -> f64 {
+        self.try_element(row, column).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_element(&self, row: usize, column: usize) -> Result<f64, LinalgError> {
+        if row == 0 || column == 0 || row > self.size || column > self.size {
+            return Err(LinalgError::IndexOutOfBounds {
+                index: (row, column),
+                dimension: (self.size, self.size),
+            });
+        }
+
+        Ok(self.band_index(row, column).map_or(0.0, |index| self.data[index]))
+    }
+
+    pub fn change_element(&mut self, row: usize, column: usize, element: f64) {
+        self.try_change_element(row, column, element)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_change_element
+    (
+        &mut self,
+        row: usize,
+        column: usize,
+        element: f64,
+    ) -> Result<(), LinalgError>
+    {
+        // entries outside of the band can't be stored
+        match self.band_index(row, column) {
+            Some(index) => {
+                self.data[index] = element;
+                Ok(())
+            }
+            None => Err(LinalgError::IndexOutOfBounds {
+                index: (row, column),
+                dimension: (self.size, self.size),
+            }),
+        }
+    }
+
+    fn columns_of_row(&self, row: usize) -> std::ops::RangeInclusive<usize> {
+        row.saturating_sub(self.lower).max(1)..=min(row + self.upper, self.size)
+    }
+}
+
+impl LinearOperator for BandedOperator {
+    fn dimension(&self) -> (usize, usize) {
+        (self.size, self.size)
+    }
+
+    fn apply(&self, x: &Vector) -> Vector {
+        check_operand(x, self.size);
+
+        let result = (1..=self.size)
+                     .map(|i| self.columns_of_row(i)
+                              .map(|j| self.element(i, j) * x.components[j - 1])
+                              .sum::<f64>())
+                     .collect::<Vec<f64>>();
+
+        Vector::create_with_vec(result)
+    }
+
+    fn transpose_apply(&self, x: &Vector) -> Option<Vector> {
+        check_operand(x, self.size);
+
+        let mut result = vec![0.0; self.size];
+
+        for i in 1..=self.size {
+            for j in self.columns_of_row(i) {
+                result[j - 1] += self.element(i, j) * x.components[i - 1];
+            }
+        }
+
+        Some(Vector::create_with_vec(result))
+    }
+
+    fn diagonal(&self) -> Option<Vector> {
+        let diagonal = (1..=self.size)
+                       .map(|i| self.element(i, i))
+                       .collect::<Vec<f64>>();
+
+        Some(Vector::create_with_vec(diagonal))
+    }
+
+    fn row(&self, i: usize) -> Option<Vec<(usize, f64)>> {
+        check_row(i, (self.size, self.size));
+
+        Some(self.columns_of_row(i).map(|j| (j, self.element(i, j))).collect())
+    }
+}
+
+// wraps a closure computing A x, for operators that are never stored
+pub struct FunctionOperator<F: Fn(&Vector) -> Vector> {
+    rows: usize,
+    columns: usize,
+    function: F,
+}
+
+impl<F: Fn(&Vector) -> Vector> FunctionOperator<F> {
+    pub fn new(rows: usize, columns: usize, function: F) -> Self {
+        Self {
+            rows,
+            columns,
+            function,
+        }
+    }
+}
+
+impl<F: Fn(&Vector) -> Vector> LinearOperator for FunctionOperator<F> {
+    fn dimension(&self) -> (usize, usize) {
+        (self.rows, self.columns)
+    }
+
+    fn apply(&self, x: &Vector) -> Vector {
+        self.try_apply(x).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    fn try_apply(&self, x: &Vector) -> Result<Vector, LinalgError> {
+        // the closure is trusted with neither the size of x nor the size
+        // of what it returns
+        if x.size() != self.columns {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (1, self.columns),
+                found: x.dimension(),
+            });
+        }
+
+        let result = (self.function)(x);
+
+        if result.size() != self.rows {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (1, self.rows),
+                found: result.dimension(),
+            });
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matrix_transpose_apply() {
+        let a = Matrix::new(&[&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]]);
+        let x = Vector::new(&[1.0, -1.0]);
+
+        assert_eq!(a.transpose_apply(&x), Some(&a.transpose() * &x));
+    }
+
+    #[test]
+    #[should_panic]
+    fn matrix_transpose_apply_with_wrong_size() {
+        let a = Matrix::new(&[&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]]);
+
+        a.transpose_apply(&Vector::new(&[1.0, 2.0, 3.0]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn diagonal_apply_with_wrong_size() {
+        let d = DiagonalOperator::new(Vector::new(&[1.0, 2.0, 3.0]));
+
+        d.apply(&Vector::new(&[1.0, 2.0]));
+    }
+
+    #[test]
+    fn try_transpose_apply_checks_the_operand() {
+        let a = Matrix::new(&[&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]]);
+        let x = Vector::new(&[1.0, -1.0]);
+        let mismatch = Err(LinalgError::DimensionMismatch { expected: (1, 2), found: (1, 3) });
+        let y = Vector::new(&[1.0, 2.0, 3.0]);
+
+        assert_eq!(a.try_transpose_apply(&x), Ok(Some(&a.transpose() * &x)));
+        assert_eq!(a.try_transpose_apply(&y), mismatch);
+
+        let mut banded = BandedOperator::new(3, 1, 0);
+        banded.change_element(2, 1, 4.0);
+
+        assert_eq!(banded.try_transpose_apply(&y), Ok(Some(Vector::new(&[8.0, 0.0, 0.0]))));
+        assert!(banded.try_transpose_apply(&x).is_err());
+
+        // operators without a transpose only report the size mismatch
+        let f = FunctionOperator::new(2, 3, |x: &Vector| Vector::new(&[x.components[0], 0.0]));
+
+        assert_eq!(f.try_transpose_apply(&x), Ok(None));
+        assert!(f.try_transpose_apply(&y).is_err());
+    }
+
+    #[test]
+    fn function_operator_checks_both_sizes() {
+        let f = FunctionOperator::new(2, 3, |x: &Vector| Vector::new(&[x.components[0], x.components[2]]));
+        let wrong_output = FunctionOperator::new(2, 2, |x: &Vector| x.clone());
+        let x = Vector::new(&[1.0, 2.0, 3.0]);
+
+        assert_eq!(f.try_apply(&x), Ok(Vector::new(&[1.0, 3.0])));
+        assert_eq!(f.try_apply(&Vector::new(&[1.0, 2.0])),
+                   Err(LinalgError::DimensionMismatch { expected: (1, 3), found: (1, 2) }));
+        assert_eq!(wrong_output.try_apply(&Vector::new(&[1.0, 2.0])), Ok(Vector::new(&[1.0, 2.0])));
+
+        let wrong_output = FunctionOperator::new(3, 2, |x: &Vector| x.clone());
+
+        assert_eq!(wrong_output.try_apply(&Vector::new(&[1.0, 2.0])),
+                   Err(LinalgError::DimensionMismatch { expected: (1, 3), found: (1, 2) }));
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn diagonal_row_out_of_range() {
+        let d = DiagonalOperator::new(Vector::new(&[1.0, 2.0, 3.0]));
+
+        assert_eq!(d.row(3), Some(vec![(3, 3.0)]));
+        d.row(4);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn banded_row_out_of_range() {
+        BandedOperator::new(3, 1, 1).row(0);
+    }
+}
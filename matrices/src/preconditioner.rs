@@ -0,0 +1,587 @@
+use crate::*;
+
+// approximation M of a matrix A whose inverse is cheap to apply.
+// `apply` returns z = M^-1 r
+pub trait Preconditioner {
+    fn dimension(&self) -> usize;
+
+    fn apply(&self, r: &Vector) -> Vector;
+}
+
+// nonzeros of each row of a triangular factor as 0-based (column, value),
+// without the diagonal
+type TriangularRows = Vec<Vec<(usize, f64)>>;
+
+fn strict_rows(data: &[f64], n: usize, lower: bool) -> TriangularRows {
+    (0..n).map(|i| {
+        let columns = if lower { 0..i } else { (i + 1)..n };
+
+        columns.filter(|&j| data[i * n + j] != 0.0)
+               .map(|j| (j, data[i * n + j]))
+               .collect()
+    }).collect()
+}
+
+fn forward_solve(lower: &TriangularRows, diagonal: Option<&[f64]>, r: &[f64]) -> Vec<f64> {
+    // diagonal None means a unit diagonal
+    let mut y = r.to_vec();
+
+    for i in 0..y.len() {
+        let sum = lower[i].iter().map(|&(j, a)| a * y[j]).sum::<f64>();
+        y[i] -= sum;
+
+        if let Some(diagonal) = diagonal {
+            y[i] /= diagonal[i];
+        }
+    }
+
+    y
+}
+
+fn backward_solve(upper: &TriangularRows, diagonal: &[f64], r: &[f64]) -> Vec<f64> {
+    let mut z = r.to_vec();
+
+    for i in (0..z.len()).rev() {
+        let sum = upper[i].iter().map(|&(j, a)| a * z[j]).sum::<f64>();
+        z[i] = (z[i] - sum) / diagonal[i];
+    }
+
+    z
+}
+
+fn nonzero_diagonal(matrix: &Matrix) -> Result<Vec<f64>, LinalgError> {
+    matrix.check_square()?;
+
+    let n = matrix.dimension().0;
+    let diagonal = (0..n).map(|i| matrix.data[i * n + i]).collect::<Vec<f64>>();
+
+    if diagonal.contains(&0.0) {
+        return Err(LinalgError::Singular);
+    }
+
+    Ok(diagonal)
+}
+
+pub struct JacobiPreconditioner {
+    inverse_diagonal: Vec<f64>,
+}
+
+impl JacobiPreconditioner {
+    pub fn new(matrix: &Matrix) -> Self {
+        Self::try_new(matrix).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_new(matrix: &Matrix) -> Result<Self, LinalgError> {
+        // M = D
+        let inverse_diagonal = nonzero_diagonal(matrix)?
+                               .iter()
+                               .map(|d| 1.0 / d)
+                               .collect();
+
+        Ok(Self { inverse_diagonal })
+    }
+}
+
+impl Preconditioner for JacobiPreconditioner {
+    fn dimension(&self) -> usize {
+        self.inverse_diagonal.len()
+    }
+
+    fn apply(&self, r: &Vector) -> Vector {
+        let z = r.components.iter()
+                .zip(self.inverse_diagonal.iter())
+                .map(|(ri, di)| ri * di)
+                .collect::<Vec<f64>>();
+
+        Vector::create_with_vec(z)
+    }
+}
+
+pub struct SsorPreconditioner {
+    lower: TriangularRows,
+    upper: TriangularRows,
+    // D / omega
+    scaled_diagonal: Vec<f64>,
+    omega: f64,
+}
+
+impl SsorPreconditioner {
+    pub fn new(matrix: &Matrix, omega: f64) -> Self {
+        Self::try_new(matrix, omega).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_new(matrix: &Matrix, omega: f64) -> Result<Self, LinalgError> {
+        // M = w / (2 - w) (D / w + L) (D / w)^-1 (D / w + U), with 0 < w < 2.
+        // w = 1 gives symmetric gauss seidel
+        if !(omega > 0.0 && omega < 2.0) {
+            return Err(LinalgError::InvalidParameter {
+                parameter: "omega",
+                requirement: "in (0, 2)",
+            });
+        }
+
+        let diagonal = nonzero_diagonal(matrix)?;
+        let n = diagonal.len();
+
+        Ok(Self {
+            lower: strict_rows(&matrix.data, n, true),
+            upper: strict_rows(&matrix.data, n, false),
+            scaled_diagonal: diagonal.iter().map(|d| d / omega).collect(),
+            omega,
+        })
+    }
+}
+
+impl Preconditioner for SsorPreconditioner {
+    fn dimension(&self) -> usize {
+        self.scaled_diagonal.len()
+    }
+
+    fn apply(&self, r: &Vector) -> Vector {
+        let y = forward_solve(&self.lower, Some(&self.scaled_diagonal), &r.components);
+        let t = y.iter()
+                .zip(self.scaled_diagonal.iter())
+                .map(|(yi, di)| yi * di)
+                .collect::<Vec<f64>>();
+        let factor = (2.0 - self.omega) / self.omega;
+        let z = backward_solve(&self.upper, &self.scaled_diagonal, &t)
+                .into_iter()
+                .map(|zi| zi * factor)
+                .collect();
+
+        Vector::create_with_vec(z)
+    }
+}
+
+pub struct IncompleteCholesky {
+    // strict lower part of L by rows, and the same entries by columns (L^T)
+    lower: TriangularRows,
+    upper: TriangularRows,
+    diagonal: Vec<f64>,
+}
+
+impl IncompleteCholesky {
+    pub fn new(matrix: &Matrix) -> Self {
+        Self::try_new(matrix).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_new(matrix: &Matrix) -> Result<Self, LinalgError> {
+        // IC(0): cholesky restricted to the nonzero pattern of A, M = L L^T.
+        // may fail even for positive definite matrices
+        matrix.check_square()?;
+
+        if *matrix != matrix.transpose() {
+            return Err(LinalgError::NotSymmetric);
+        }
+
+        let n = matrix.dimension().0;
+        let a = &matrix.data;
+        let mut l = vec![0.0; n * n];
+
+        for i in 0..n {
+            l[i * n..i * n + i + 1].copy_from_slice(&a[i * n..i * n + i + 1]);
+        }
+
+        for k in 0..n {
+            let pivot = l[k * n + k];
+
+            if pivot <= 0.0 {
+                return Err(LinalgError::NotPositiveDefinite { pivot: k + 1 });
+            }
+
+            let lkk = pivot.sqrt();
+            l[k * n + k] = lkk;
+
+            for i in (k + 1)..n {
+                l[i * n + k] /= lkk;
+            }
+
+            for j in (k + 1)..n {
+                let ljk = l[j * n + k];
+
+                if ljk == 0.0 {
+                    continue;
+                }
+
+                for i in j..n {
+                    if a[i * n + j] != 0.0 {
+                        l[i * n + j] -= l[i * n + k] * ljk;
+                    }
+                }
+            }
+        }
+
+        let lower = strict_rows(&l, n, true);
+        let mut upper = vec![vec![]; n];
+
+        for (i, row) in lower.iter().enumerate() {
+            for &(j, value) in row.iter() {
+                upper[j].push((i, value));
+            }
+        }
+
+        Ok(Self {
+            lower,
+            upper,
+            diagonal: (0..n).map(|i| l[i * n + i]).collect(),
+        })
+    }
+}
+
+impl Preconditioner for IncompleteCholesky {
+    fn dimension(&self) -> usize {
+        self.diagonal.len()
+    }
+
+    fn apply(&self, r: &Vector) -> Vector {
+        let y = forward_solve(&self.lower, Some(&self.diagonal), &r.components);
+
+        Vector::create_with_vec(backward_solve(&self.upper, &self.diagonal, &y))
+    }
+}
+
+pub struct IncompleteLu {
+    // L has a unit diagonal that is not stored
+    lower: TriangularRows,
+    upper: TriangularRows,
+    diagonal: Vec<f64>,
+}
+
+impl IncompleteLu {
+    pub fn new(matrix: &Matrix) -> Self {
+        Self::try_new(matrix).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_new(matrix: &Matrix) -> Result<Self, LinalgError> {
+        // ILU(0): gaussian elimination without pivoting that only updates
+        // the entries in the nonzero pattern of A, M = L U
+        matrix.check_square()?;
+
+        let n = matrix.dimension().0;
+        let pattern = &matrix.data;
+        let mut a = matrix.data.clone();
+
+        for i in 1..n {
+            for k in 0..i {
+                if pattern[i * n + k] == 0.0 {
+                    continue;
+                }
+
+                let pivot = a[k * n + k];
+
+                if pivot == 0.0 {
+                    return Err(LinalgError::Singular);
+                }
+
+                let factor = a[i * n + k] / pivot;
+                a[i * n + k] = factor;
+
+                for j in (k + 1)..n {
+                    if pattern[i * n + j] != 0.0 {
+                        a[i * n + j] -= factor * a[k * n + j];
+                    }
+                }
+            }
+        }
+
+        let diagonal = (0..n).map(|i| a[i * n + i]).collect::<Vec<f64>>();
+
+        if diagonal.contains(&0.0) {
+            return Err(LinalgError::Singular);
+        }
+
+        Ok(Self {
+            lower: strict_rows(&a, n, true),
+            upper: strict_rows(&a, n, false),
+            diagonal,
+        })
+    }
+}
+
+impl Preconditioner for IncompleteLu {
+    fn dimension(&self) -> usize {
+        self.diagonal.len()
+    }
+
+    fn apply(&self, r: &Vector) -> Vector {
+        let y = forward_solve(&self.lower, None, &r.components);
+
+        Vector::create_with_vec(backward_solve(&self.upper, &self.diagonal, &y))
+    }
+}
+
+impl Solver {
+    pub fn preconditioned_conjugate_gradient<A: LinearOperator, P: Preconditioner>
+    (
+        &self,
+        operator: &A,
+        preconditioner: &P,
+        b: &Vector,
+        error: f64,
+        max_iter: usize,
+    ) -> IterativeResult
+    {
+        self.try_preconditioned_conjugate_gradient(operator, preconditioner, b, error, max_iter)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_preconditioned_conjugate_gradient<A: LinearOperator, P: Preconditioner>
+    (
+        &self,
+        operator: &A,
+        preconditioner: &P,
+        b: &Vector,
+        error: f64,
+        max_iter: usize,
+    ) -> Result<IterativeResult, LinalgError>
+    {
+        // cg on M^-1 A x = M^-1 b, M has to be symmetric positive definite
+        // too (jacobi, ssor and incomplete cholesky are, for spd A).
+        // stops when |b - A x| <= error * |b|
+        self.check_operator_system(operator, b)?;
+
+        let n = b.size();
+
+        if preconditioner.dimension() != n {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (n, n),
+                found: (preconditioner.dimension(), preconditioner.dimension()),
+            });
+        }
+
+        let tolerance = error * b.magnitude();
+
+        let mut x = Vector::zero(n);
+        let mut r = b.clone();
+        let mut z = preconditioner.apply(&r);
+        let mut p = z.clone();
+        let mut rz = r.dot_product(&z);
+        let mut residual = r.magnitude();
+        let mut history = vec![residual];
+
+        if residual <= tolerance {
+            return Ok(IterativeResult::new(x, 0, history, StopReason::Converged));
+        }
+
+        for k in 1..=max_iter {
+            let ap = operator.try_apply(&p)?;
+            let pap = p.dot_product(&ap);
+
+            if pap <= 0.0 {
+                return Ok(IterativeResult::new(x, k - 1, history, StopReason::Breakdown));
+            }
+
+            let alpha = rz / pap;
+            x = &x + &(&p * alpha);
+            r = &r - &(&ap * alpha);
+            residual = r.magnitude();
+            history.push(residual);
+
+            if residual <= tolerance {
+                return Ok(IterativeResult::new(x, k, history, StopReason::Converged));
+            }
+
+            z = preconditioner.apply(&r);
+            let rz_new = r.dot_product(&z);
+            p = &z + &(&p * (rz_new / rz));
+            rz = rz_new;
+        }
+
+        Ok(IterativeResult::new(x, max_iter, history, StopReason::MaxIterations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tridiagonal(n: usize, lower: f64, diagonal: f64, upper: f64) -> Matrix {
+        let mut a = Matrix::zero(n, n);
+
+        for i in 0..n {
+            a.data[i * n + i] = diagonal + i as f64 * 0.1;
+
+            if i + 1 < n {
+                a.data[i * n + i + 1] = upper;
+                a.data[(i + 1) * n + i] = lower;
+            }
+        }
+
+        a
+    }
+
+    // 5 point laplacian on a k x k grid, whose factors fill in
+    fn laplacian(k: usize) -> Matrix {
+        let n = k * k;
+        let mut a = Matrix::zero(n, n);
+
+        for i in 0..n {
+            a.data[i * n + i] = 4.0;
+
+            if i % k + 1 < k {
+                a.data[i * n + i + 1] = -1.0;
+                a.data[(i + 1) * n + i] = -1.0;
+            }
+
+            if i + k < n {
+                a.data[i * n + i + k] = -1.0;
+                a.data[(i + k) * n + i] = -1.0;
+            }
+        }
+
+        a
+    }
+
+    fn to_matrix(rows: &TriangularRows, diagonal: &[f64]) -> Matrix {
+        let n = diagonal.len();
+        let mut m = Matrix::zero(n, n);
+
+        for (i, row) in rows.iter().enumerate() {
+            m.data[i * n + i] = diagonal[i];
+
+            for &(j, value) in row.iter() {
+                m.data[i * n + j] = value;
+            }
+        }
+
+        m
+    }
+
+    // largest |(L U - A)_ij| over the nonzero pattern of A
+    fn pattern_difference(product: &Matrix, a: &Matrix) -> f64 {
+        product.data.iter()
+               .zip(a.data.iter())
+               .filter(|(_, &x)| x != 0.0)
+               .map(|(p, x)| (p - x).abs())
+               .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn incomplete_lu_is_exact_on_tridiagonal_matrices() {
+        let a = tridiagonal(12, -1.5, 4.0, -0.5);
+        let ilu = IncompleteLu::new(&a);
+        let l = to_matrix(&ilu.lower, &[1.0; 12]);
+        let u = to_matrix(&ilu.upper, &ilu.diagonal);
+        let lu = &l * &u;
+
+        assert!(lu.data.iter().zip(a.data.iter()).all(|(x, y)| (x - y).abs() < 1e-14));
+
+        let b = Vector::create_with_vec((1..=12).map(|i| i as f64).collect());
+        let residual = &b - &(&a * &ilu.apply(&b));
+
+        assert!(residual.components.iter().all(|r| r.abs() < 1e-13));
+    }
+
+    #[test]
+    fn incomplete_factors_match_on_the_pattern() {
+        let a = laplacian(4);
+        let n = 16;
+
+        let ilu = IncompleteLu::new(&a);
+        let lu = &to_matrix(&ilu.lower, &[1.0; 16]) * &to_matrix(&ilu.upper, &ilu.diagonal);
+
+        assert!(pattern_difference(&lu, &a) < 1e-14);
+        assert!(lu != a);
+
+        let ic = IncompleteCholesky::new(&a);
+        let l = to_matrix(&ic.lower, &ic.diagonal);
+        let llt = &l * &l.transpose();
+
+        assert!(pattern_difference(&llt, &a) < 1e-14);
+        assert!((0..n).all(|i| ic.upper[i].iter().all(|&(j, v)| l.data[j * n + i] == v)));
+
+        // without fill in, IC(0) is the cholesky factor
+        let a = tridiagonal(8, -1.0, 3.0, -1.0);
+        let ic = IncompleteCholesky::new(&a);
+        let l = to_matrix(&ic.lower, &ic.diagonal);
+
+        assert!((&(&l * &l.transpose()) - &a).data.iter().all(|d| d.abs() < 1e-14));
+    }
+
+    #[test]
+    fn preconditioned_cg_needs_fewer_iterations() {
+        let solver = Solver::new();
+        let a = laplacian(10);
+        let b = Vector::create_with_vec((0..100).map(|i| 1.0 + (i % 7) as f64).collect());
+        let plain = solver.conjugate_gradient(&a, &b, 1e-10, 500);
+
+        let preconditioned = [
+            solver.preconditioned_conjugate_gradient(&a, &IncompleteCholesky::new(&a), &b, 1e-10, 500),
+            solver.preconditioned_conjugate_gradient(&a, &SsorPreconditioner::new(&a, 1.5), &b, 1e-10, 500),
+        ];
+
+        for result in preconditioned.iter() {
+            assert!(result.converged());
+            assert!(result.iterations() < plain.iterations());
+            assert_eq!(result.residual_history().len(), result.iterations() + 1);
+            assert!((&b - &(&a * &result.solution())).magnitude() <= 1e-10 * b.magnitude());
+        }
+
+        // badly scaled rows and columns are what jacobi undoes
+        let n = 30;
+        let mut scaled = tridiagonal(n, -1.0, 2.5, -1.0);
+
+        for i in 0..n {
+            for j in 0..n {
+                scaled.data[i * n + j] *= 10f64.powi((i % 5) as i32 + (j % 5) as i32);
+            }
+        }
+
+        let b = Vector::create_with_vec(vec![1.0; n]);
+        let plain = solver.conjugate_gradient(&scaled, &b, 1e-8, 1000);
+        let diagonal = JacobiPreconditioner::new(&scaled);
+        let jacobi = solver.preconditioned_conjugate_gradient(&scaled, &diagonal, &b, 1e-8, 1000);
+
+        assert!(jacobi.converged());
+        assert!(jacobi.iterations() < plain.iterations());
+    }
+
+    #[test]
+    fn ssor_with_unit_omega_is_symmetric_gauss_seidel() {
+        // M^-1 is symmetric for symmetric A
+        let a = laplacian(3);
+        let ssor = SsorPreconditioner::new(&a, 1.0);
+        let columns = (1..=9).map(|j| ssor.apply(&Vector::canonical(j, 9))).collect::<Vec<Vector>>();
+
+        for i in 0..9 {
+            for j in 0..9 {
+                assert!((columns[j].components[i] - columns[i].components[j]).abs() < 1e-15);
+            }
+        }
+    }
+
+    #[test]
+    fn preconditioner_errors() {
+        let a = laplacian(2);
+
+        for omega in [0.0, 2.0, -1.0, f64::NAN] {
+            assert!(matches!(SsorPreconditioner::try_new(&a, omega),
+                             Err(LinalgError::InvalidParameter { parameter: "omega", .. })));
+        }
+
+        let mut zero_diagonal = a.clone();
+        zero_diagonal.change_element(2, 2, 0.0);
+
+        assert!(matches!(JacobiPreconditioner::try_new(&zero_diagonal), Err(LinalgError::Singular)));
+        assert!(matches!(SsorPreconditioner::try_new(&zero_diagonal, 1.0), Err(LinalgError::Singular)));
+
+        let mut unsymmetric = a.clone();
+        unsymmetric.change_element(1, 4, 0.5);
+
+        assert!(matches!(IncompleteCholesky::try_new(&unsymmetric), Err(LinalgError::NotSymmetric)));
+
+        let indefinite = Matrix::new(&[&[1.0, 2.0], &[2.0, 1.0]]);
+
+        assert!(matches!(IncompleteCholesky::try_new(&indefinite),
+                         Err(LinalgError::NotPositiveDefinite { pivot: 2 })));
+        assert!(matches!(IncompleteLu::try_new(&Matrix::new(&[&[0.0, 1.0], &[1.0, 0.0]])),
+                         Err(LinalgError::Singular)));
+
+        let too_small = JacobiPreconditioner::new(&a);
+        let b = Vector::zero(9);
+        let result = Solver::new()
+                     .try_preconditioned_conjugate_gradient(&laplacian(3), &too_small, &b, 1e-10, 10);
+
+        assert!(matches!(result, Err(LinalgError::DimensionMismatch { .. })));
+    }
+}
@@ -0,0 +1,268 @@
+use crate::*;
+
+// one elementary row operation, rows are 1-based
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowOperation<T = f64> {
+    Swap(usize, usize),
+    // row = factor * row
+    Scale {
+        row: usize,
+        factor: T,
+    },
+    // target = target - factor * source, as in Eliminator::multiply_rows
+    Subtract {
+        target: usize,
+        source: usize,
+        factor: T,
+    },
+}
+
+// operations recorded by the eliminator, in the order they were applied.
+// together they are the matrix E with E A = echelon form (or rref) of A,
+// kept as swaps and multipliers instead of an m x m matrix. pivoting
+// strategies that swap columns reduce E A Q instead, and the permutation
+// Q is kept apart from the row operations
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowOperations<T = f64> {
+    rows: usize,
+    operations: Vec<RowOperation<T>>,
+    // column j of E A Q is column column_permutation[j - 1] of A
+    column_permutation: Vec<usize>,
+}
+
+impl<T: Scalar> RowOperations<T> {
+    pub(crate) fn new(rows: usize, columns: usize) -> Self {
+        Self {
+            rows,
+            operations: vec![],
+            column_permutation: (1..=columns).collect(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, operation: RowOperation<T>) {
+        self.operations.push(operation);
+    }
+
+    pub(crate) fn swap_columns(&mut self, column1: usize, column2: usize) {
+        self.column_permutation.swap(column1 - 1, column2 - 1);
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    pub fn operations(&self) -> &[RowOperation<T>] {
+        &self.operations
+    }
+
+    // the row swaps only, as the permutation P they compose to:
+    // row i of P A is row permutation[i - 1] of A
+    pub fn permutation(&self) -> Vec<usize> {
+        let mut permutation = (1..=self.rows).collect::<Vec<usize>>();
+
+        for operation in self.operations.iter() {
+            if let RowOperation::Swap(i, j) = operation {
+                permutation.swap(i - 1, j - 1);
+            }
+        }
+
+        permutation
+    }
+
+    pub fn column_permutation(&self) -> &[usize] {
+        &self.column_permutation
+    }
+
+    // only the row operations are applied, matrix = E matrix
+    pub fn apply(&self, matrix: &mut Matrix<T>) {
+        self.try_apply(matrix).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_apply(&self, matrix: &mut Matrix<T>) -> Result<(), LinalgError> {
+        let (m, n) = matrix.dimension();
+
+        if m != self.rows {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (self.rows, n),
+                found: (m, n),
+            });
+        }
+
+        let eliminator = Eliminator::new();
+
+        for operation in self.operations.iter() {
+            match operation {
+                RowOperation::Swap(i, j) => eliminator.swap_rows(*i, *j, matrix),
+                RowOperation::Scale { row, factor } => {
+                    eliminator.multiply_row_by_number(*row, factor.clone(), matrix)
+                }
+                RowOperation::Subtract { target, source, factor } => {
+                    eliminator.multiply_rows(*target, *source, factor.clone(), matrix)
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn apply_to_vector(&self, vector: &Vector<T>) -> Vector<T> {
+        self.try_apply_to_vector(vector).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_apply_to_vector(&self, vector: &Vector<T>) -> Result<Vector<T>, LinalgError> {
+        // E v, e.g. the right hand side of a system reduced with the matrix
+        let mut column = Matrix::from_data(vector.size(), 1, vector.components.clone());
+        self.try_apply(&mut column)?;
+
+        Ok(Vector::create_with_vec(column.data))
+    }
+
+    // the accumulated E, only needed when the operations have to be
+    // combined with other matrices
+    pub fn to_matrix(&self) -> Matrix<T> {
+        let mut result = Matrix::identity(self.rows);
+        self.apply(&mut result);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rational::Rational;
+
+    fn r(p: i64) -> Rational {
+        Rational::from_integer(p)
+    }
+
+    fn example() -> Matrix<Rational> {
+        Matrix::new(&[&[r(0), r(2), r(1), r(4)],
+                      &[r(1), r(1), r(1), r(1)],
+                      &[r(3), r(-1), r(2), r(5)]])
+    }
+
+    fn elementary(operation: &RowOperation<Rational>, size: usize) -> Matrix<Rational> {
+        // the matrix the operation left multiplies by, built entry by entry
+        let mut e = Matrix::identity(size);
+
+        match operation {
+            RowOperation::Swap(i, j) => {
+                e.change_element(*i, *i, r(0));
+                e.change_element(*j, *j, r(0));
+                e.change_element(*i, *j, r(1));
+                e.change_element(*j, *i, r(1));
+            }
+            RowOperation::Scale { row, factor } => e.change_element(*row, *row, factor.clone()),
+            RowOperation::Subtract { target, source, factor } => {
+                e.change_element(*target, *source, -factor.clone())
+            }
+        }
+
+        e
+    }
+
+    #[test]
+    fn in_place_operations_match_elementary_products() {
+        let eliminator = Eliminator::new();
+        let a = example();
+        let operations = [RowOperation::Swap(1, 3),
+                          RowOperation::Swap(2, 2),
+                          RowOperation::Scale { row: 2, factor: Rational::new(-2, 3) },
+                          RowOperation::Subtract { target: 1, source: 3, factor: Rational::new(5, 2) },
+                          RowOperation::Subtract { target: 3, source: 1, factor: r(4) }];
+
+        for operation in operations.iter() {
+            let mut in_place = a.clone();
+
+            match operation {
+                RowOperation::Swap(i, j) => eliminator.swap_rows(*i, *j, &mut in_place),
+                RowOperation::Scale { row, factor } => {
+                    eliminator.multiply_row_by_number(*row, factor.clone(), &mut in_place)
+                }
+                RowOperation::Subtract { target, source, factor } => {
+                    eliminator.multiply_rows(*target, *source, factor.clone(), &mut in_place)
+                }
+            }
+
+            assert_eq!(in_place, &elementary(operation, 3) * &a);
+        }
+
+        let mut swapped = a.clone();
+        eliminator.swap_columns(1, 4, &mut swapped);
+
+        assert_eq!(swapped.get_column(1), a.get_column(4));
+        assert_eq!(swapped.get_column(4), a.get_column(1));
+    }
+
+    #[test]
+    fn replaying_the_recorded_operations_reduces_the_matrix() {
+        let eliminator = Eliminator::new();
+        let a = example();
+
+        let mut echelon = a.clone();
+        let (pivots, operations) = eliminator.recorded_row_echelon_form(&mut echelon, 0.0);
+        let mut unrecorded = a.clone();
+
+        assert_eq!(eliminator.row_echelon_form(&mut unrecorded), pivots);
+        assert_eq!(unrecorded, echelon);
+
+        let mut reduced = a.clone();
+        let (rref_pivots, rref_operations) = eliminator.recorded_rref(&mut reduced, 0.0);
+
+        assert_eq!(rref_pivots, pivots);
+
+        for (operations, expected) in [(&operations, &echelon), (&rref_operations, &reduced)] {
+            let mut replayed = a.clone();
+            operations.apply(&mut replayed);
+
+            assert_eq!(&replayed, expected);
+            assert_eq!(&(&operations.to_matrix() * &a), expected);
+            assert_eq!(operations.rows(), 3);
+            assert_eq!(operations.len(), operations.operations().len());
+            assert!(!operations.is_empty());
+        }
+
+        // the 3 of the last row goes on top, then the 2 of the first row
+        // beats the 4/3 left in the second
+        assert_eq!(operations.permutation(), vec![3, 1, 2]);
+        assert_eq!(operations.column_permutation(), &[1, 2, 3, 4]);
+
+        // a right hand side reduced along with the matrix
+        let b = Vector::new(&[r(1), r(2), r(3)]);
+
+        assert_eq!(rref_operations.apply_to_vector(&b), &rref_operations.to_matrix() * &b);
+        assert!(rref_operations.try_apply(&mut Matrix::identity(2)).is_err());
+        assert!(rref_operations.try_apply_to_vector(&Vector::new(&[r(1)])).is_err());
+    }
+
+    #[test]
+    fn replay_with_column_swaps() {
+        // E A Q: the row operations reproduce the echelon form once the
+        // columns of A are permuted like the recorded permutation
+        let eliminator = Eliminator::with_pivoting(PivotStrategy::Complete, 0.0);
+        let a = example();
+        let mut echelon = a.clone();
+        let (_, operations) = eliminator.recorded_row_echelon_form(&mut echelon, 0.0);
+
+        let columns = operations.column_permutation()
+                      .iter()
+                      .map(|&j| a.get_column(j).unwrap())
+                      .collect::<Vec<Vector<Rational>>>();
+        let mut permuted = Matrix::create_with_vectors(&columns).transpose();
+
+        assert_ne!(operations.column_permutation(), &[1, 2, 3, 4]);
+
+        operations.apply(&mut permuted);
+
+        assert_eq!(permuted, echelon);
+    }
+}
@@ -0,0 +1,771 @@
+use crate::*;
+
+// coordinate format, used to assemble a matrix entry by entry.
+// entries are kept as 0-based triplets and duplicates are summed
+// when converting to csr or csc
+#[derive(Debug, Clone, PartialEq)]
+pub struct CooMatrix<T = f64> {
+    rows: usize,
+    columns: usize,
+    entries: Vec<(usize, usize, T)>,
+}
+
+impl<T: Scalar> CooMatrix<T> {
+    pub fn new(rows: usize, columns: usize) -> Self {
+        Self {
+            rows,
+            columns,
+            entries: vec![],
+        }
+    }
+
+    pub fn push(&mut self, row: usize, column: usize, value: T) {
+        self.try_push(row, column, value)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_push(&mut self, row: usize, column: usize, value: T) -> Result<(), LinalgError> {
+        if row == 0 || column == 0 || row > self.rows || column > self.columns {
+            return Err(LinalgError::IndexOutOfBounds {
+                index: (row, column),
+                dimension: self.dimension(),
+            });
+        }
+
+        self.entries.push((row - 1, column - 1, value));
+
+        Ok(())
+    }
+
+    pub fn dimension(&self) -> (usize, usize) {
+        (self.rows, self.columns)
+    }
+
+    pub fn nnz(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn to_csr(&self) -> CsrMatrix<T> {
+        let mut entries = self.entries.clone();
+        entries.sort_by_key(|&(i, j, _)| (i, j));
+
+        let mut row_pointers = vec![0; self.rows + 1];
+        let mut column_indices = Vec::with_capacity(entries.len());
+        let mut values: Vec<T> = Vec::with_capacity(entries.len());
+        let mut last = None;
+
+        for (i, j, value) in entries {
+            if last == Some((i, j)) {
+                let sum = values.pop().unwrap() + value;
+                values.push(sum);
+            } else {
+                row_pointers[i + 1] += 1;
+                column_indices.push(j);
+                values.push(value);
+                last = Some((i, j));
+            }
+        }
+
+        for i in 0..self.rows {
+            row_pointers[i + 1] += row_pointers[i];
+        }
+
+        CsrMatrix {
+            rows: self.rows,
+            columns: self.columns,
+            row_pointers,
+            column_indices,
+            values,
+        }
+    }
+
+    pub fn to_csc(&self) -> CscMatrix<T> {
+        self.to_csr().to_csc()
+    }
+
+    pub fn to_dense(&self) -> Matrix<T> {
+        self.to_csr().to_dense()
+    }
+}
+
+// compressed sparse rows: the entries of row i are
+// values[row_pointers[i]..row_pointers[i + 1]], sorted by column
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsrMatrix<T = f64> {
+    rows: usize,
+    columns: usize,
+    row_pointers: Vec<usize>,
+    column_indices: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T: Scalar> CsrMatrix<T> {
+    pub fn zero(rows: usize, columns: usize) -> Self {
+        Self {
+            rows,
+            columns,
+            row_pointers: vec![0; rows + 1],
+            column_indices: vec![],
+            values: vec![],
+        }
+    }
+
+    pub fn identity(size: usize) -> Self {
+        Self {
+            rows: size,
+            columns: size,
+            row_pointers: (0..=size).collect(),
+            column_indices: (0..size).collect(),
+            values: vec![T::one(); size],
+        }
+    }
+
+    pub fn from_dense(matrix: &Matrix<T>) -> Self {
+        // exact zeros are not stored
+        let (m, n) = matrix.dimension();
+        let mut row_pointers = Vec::with_capacity(m + 1);
+        let mut column_indices = vec![];
+        let mut values = vec![];
+
+        row_pointers.push(0);
+
+        for i in 0..m {
+            for j in 0..n {
+                let value = &matrix.data[i * n + j];
+
+                if !value.is_zero() {
+                    column_indices.push(j);
+                    values.push(value.clone());
+                }
+            }
+
+            row_pointers.push(values.len());
+        }
+
+        Self {
+            rows: m,
+            columns: n,
+            row_pointers,
+            column_indices,
+            values,
+        }
+    }
+
+    pub fn to_dense(&self) -> Matrix<T> {
+        let n = self.columns;
+        let mut dense = Matrix::zero(self.rows, n);
+
+        for i in 0..self.rows {
+            for k in self.row_pointers[i]..self.row_pointers[i + 1] {
+                dense.data[i * n + self.column_indices[k]] = self.values[k].clone();
+            }
+        }
+
+        dense
+    }
+
+    pub fn to_coo(&self) -> CooMatrix<T> {
+        let mut coo = CooMatrix::new(self.rows, self.columns);
+
+        for i in 0..self.rows {
+            for k in self.row_pointers[i]..self.row_pointers[i + 1] {
+                coo.entries.push((i, self.column_indices[k], self.values[k].clone()));
+            }
+        }
+
+        coo
+    }
+
+    pub fn to_csc(&self) -> CscMatrix<T> {
+        // the csr arrays of A^T are the csc arrays of A
+        let transpose = self.transpose();
+
+        CscMatrix {
+            rows: self.rows,
+            columns: self.columns,
+            column_pointers: transpose.row_pointers,
+            row_indices: transpose.column_indices,
+            values: transpose.values,
+        }
+    }
+
+    pub fn dimension(&self) -> (usize, usize) {
+        (self.rows, self.columns)
+    }
+
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn element(&self, row: usize, column: usize) -> T {
+        self.try_element(row, column).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_element(&self, row: usize, column: usize) -> Result<T, LinalgError> {
+        if row == 0 || column == 0 || row > self.rows || column > self.columns {
+            return Err(LinalgError::IndexOutOfBounds {
+                index: (row, column),
+                dimension: self.dimension(),
+            });
+        }
+
+        let (start, end) = (self.row_pointers[row - 1], self.row_pointers[row]);
+
+        match self.column_indices[start..end].binary_search(&(column - 1)) {
+            Ok(k) => Ok(self.values[start + k].clone()),
+            Err(_) => Ok(T::zero()),
+        }
+    }
+
+    pub fn diagonal(&self) -> Vector<T> {
+        let components = (1..=min(self.rows, self.columns))
+                         .map(|i| self.element(i, i))
+                         .collect::<Vec<T>>();
+
+        Vector::create_with_vec(components)
+    }
+
+    pub fn transpose(&self) -> Self {
+        // counting sort by column, rows are visited in order so
+        // the column indices of the result come out sorted
+        let mut row_pointers = vec![0; self.columns + 1];
+
+        for &j in self.column_indices.iter() {
+            row_pointers[j + 1] += 1;
+        }
+
+        for j in 0..self.columns {
+            row_pointers[j + 1] += row_pointers[j];
+        }
+
+        let mut next = row_pointers.clone();
+        let mut column_indices = vec![0; self.nnz()];
+        let mut values = vec![T::zero(); self.nnz()];
+
+        for i in 0..self.rows {
+            for k in self.row_pointers[i]..self.row_pointers[i + 1] {
+                let j = self.column_indices[k];
+                let position = next[j];
+
+                column_indices[position] = i;
+                values[position] = self.values[k].clone();
+                next[j] += 1;
+            }
+        }
+
+        Self {
+            rows: self.columns,
+            columns: self.rows,
+            row_pointers,
+            column_indices,
+            values,
+        }
+    }
+
+    fn merge(&self, other: &Self, negate: bool) -> Result<Self, LinalgError> {
+        // self + other or self - other, row by row over the sorted indices.
+        // entries that cancel to zero are not stored, like in from_dense
+        if self.dimension() != other.dimension() {
+            return Err(LinalgError::DimensionMismatch {
+                expected: self.dimension(),
+                found: other.dimension(),
+            });
+        }
+
+        let sign = |value: &T| if negate { -value.clone() } else { value.clone() };
+        let mut row_pointers = Vec::with_capacity(self.rows + 1);
+        let mut column_indices = vec![];
+        let mut values = vec![];
+
+        row_pointers.push(0);
+
+        for i in 0..self.rows {
+            let (mut a, a_end) = (self.row_pointers[i], self.row_pointers[i + 1]);
+            let (mut b, b_end) = (other.row_pointers[i], other.row_pointers[i + 1]);
+
+            while a < a_end || b < b_end {
+                let ja = if a < a_end { self.column_indices[a] } else { usize::MAX };
+                let jb = if b < b_end { other.column_indices[b] } else { usize::MAX };
+
+                let (j, value) = if ja == jb {
+                    a += 1;
+                    b += 1;
+                    (ja, self.values[a - 1].clone() + sign(&other.values[b - 1]))
+                } else if ja < jb {
+                    a += 1;
+                    (ja, self.values[a - 1].clone())
+                } else {
+                    b += 1;
+                    (jb, sign(&other.values[b - 1]))
+                };
+
+                if !value.is_zero() {
+                    column_indices.push(j);
+                    values.push(value);
+                }
+            }
+
+            row_pointers.push(values.len());
+        }
+
+        Ok(Self {
+            rows: self.rows,
+            columns: self.columns,
+            row_pointers,
+            column_indices,
+            values,
+        })
+    }
+
+    pub fn try_add(&self, other: &Self) -> Result<Self, LinalgError> {
+        self.merge(other, false)
+    }
+
+    pub fn try_sub(&self, other: &Self) -> Result<Self, LinalgError> {
+        self.merge(other, true)
+    }
+
+    pub fn try_mul_vector(&self, other: &Vector<T>) -> Result<Vector<T>, LinalgError> {
+        if other.size() != self.columns {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (1, self.columns),
+                found: other.dimension(),
+            });
+        }
+
+        let result = (0..self.rows)
+                     .map(|i| (self.row_pointers[i]..self.row_pointers[i + 1])
+                              .fold(T::zero(), |sum, k| {
+                                  sum + self.values[k].clone()
+                                      * other.components[self.column_indices[k]].clone()
+                              }))
+                     .collect::<Vec<T>>();
+
+        Ok(Vector::create_with_vec(result))
+    }
+}
+
+// compressed sparse columns: the entries of column j are
+// values[column_pointers[j]..column_pointers[j + 1]], sorted by row
+#[derive(Debug, Clone, PartialEq)]
+pub struct CscMatrix<T = f64> {
+    rows: usize,
+    columns: usize,
+    column_pointers: Vec<usize>,
+    row_indices: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T: Scalar> CscMatrix<T> {
+    pub fn from_dense(matrix: &Matrix<T>) -> Self {
+        CsrMatrix::from_dense(matrix).to_csc()
+    }
+
+    pub fn to_dense(&self) -> Matrix<T> {
+        let n = self.columns;
+        let mut dense = Matrix::zero(self.rows, n);
+
+        for j in 0..n {
+            for k in self.column_pointers[j]..self.column_pointers[j + 1] {
+                dense.data[self.row_indices[k] * n + j] = self.values[k].clone();
+            }
+        }
+
+        dense
+    }
+
+    fn as_transposed_csr(&self) -> CsrMatrix<T> {
+        // the csc arrays of A are the csr arrays of A^T
+        CsrMatrix {
+            rows: self.columns,
+            columns: self.rows,
+            row_pointers: self.column_pointers.clone(),
+            column_indices: self.row_indices.clone(),
+            values: self.values.clone(),
+        }
+    }
+
+    // the other way around, the csr arrays of A as the csc arrays of A^T
+    fn from_transposed_csr(csr: CsrMatrix<T>) -> Self {
+        Self {
+            rows: csr.columns,
+            columns: csr.rows,
+            column_pointers: csr.row_pointers,
+            row_indices: csr.column_indices,
+            values: csr.values,
+        }
+    }
+
+    pub fn to_csr(&self) -> CsrMatrix<T> {
+        self.as_transposed_csr().transpose()
+    }
+
+    pub fn dimension(&self) -> (usize, usize) {
+        (self.rows, self.columns)
+    }
+
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn element(&self, row: usize, column: usize) -> T {
+        self.try_element(row, column).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_element(&self, row: usize, column: usize) -> Result<T, LinalgError> {
+        if row == 0 || column == 0 || row > self.rows || column > self.columns {
+            return Err(LinalgError::IndexOutOfBounds {
+                index: (row, column),
+                dimension: self.dimension(),
+            });
+        }
+
+        let (start, end) = (self.column_pointers[column - 1], self.column_pointers[column]);
+
+        match self.row_indices[start..end].binary_search(&(row - 1)) {
+            Ok(k) => Ok(self.values[start + k].clone()),
+            Err(_) => Ok(T::zero()),
+        }
+    }
+
+    pub fn transpose(&self) -> Self {
+        Self::from_transposed_csr(self.to_csr())
+    }
+
+    fn merge(&self, other: &Self, negate: bool) -> Result<Self, LinalgError> {
+        // column by column, which is the csr merge of the transposes
+        if self.dimension() != other.dimension() {
+            return Err(LinalgError::DimensionMismatch {
+                expected: self.dimension(),
+                found: other.dimension(),
+            });
+        }
+
+        let sum = self.as_transposed_csr().merge(&other.as_transposed_csr(), negate)?;
+
+        Ok(Self::from_transposed_csr(sum))
+    }
+
+    pub fn try_add(&self, other: &Self) -> Result<Self, LinalgError> {
+        self.merge(other, false)
+    }
+
+    pub fn try_sub(&self, other: &Self) -> Result<Self, LinalgError> {
+        self.merge(other, true)
+    }
+
+    pub fn try_mul_vector(&self, other: &Vector<T>) -> Result<Vector<T>, LinalgError> {
+        if other.size() != self.columns {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (1, self.columns),
+                found: other.dimension(),
+            });
+        }
+
+        let mut result = vec![T::zero(); self.rows];
+
+        for j in 0..self.columns {
+            let xj = &other.components[j];
+
+            for k in self.column_pointers[j]..self.column_pointers[j + 1] {
+                let i = self.row_indices[k];
+                result[i] = result[i].clone() + self.values[k].clone() * xj.clone();
+            }
+        }
+
+        Ok(Vector::create_with_vec(result))
+    }
+}
+
+impl<T: Scalar> Add<&CsrMatrix<T>> for &CsrMatrix<T> {
+    type Output = CsrMatrix<T>;
+
+    fn add(self, other: &CsrMatrix<T>) -> CsrMatrix<T> {
+        self.try_add(other).unwrap_or_else(|error| panic!("{}", error))
+    }
+}
+
+impl<T: Scalar> Sub<&CsrMatrix<T>> for &CsrMatrix<T> {
+    type Output = CsrMatrix<T>;
+
+    fn sub(self, other: &CsrMatrix<T>) -> CsrMatrix<T> {
+        self.try_sub(other).unwrap_or_else(|error| panic!("{}", error))
+    }
+}
+
+impl<T: Scalar> Mul<T> for &CsrMatrix<T> {
+    type Output = CsrMatrix<T>;
+
+    fn mul(self, other: T) -> CsrMatrix<T> {
+        let mut result = self.clone();
+
+        for value in result.values.iter_mut() {
+            *value = value.clone() * other.clone();
+        }
+
+        result
+    }
+}
+
+impl Mul<&CsrMatrix> for f64 {
+    type Output = CsrMatrix;
+
+    fn mul(self, other: &CsrMatrix) -> CsrMatrix {
+        other * self
+    }
+}
+
+impl<T: Scalar> Add<&CscMatrix<T>> for &CscMatrix<T> {
+    type Output = CscMatrix<T>;
+
+    fn add(self, other: &CscMatrix<T>) -> CscMatrix<T> {
+        self.try_add(other).unwrap_or_else(|error| panic!("{}", error))
+    }
+}
+
+impl<T: Scalar> Sub<&CscMatrix<T>> for &CscMatrix<T> {
+    type Output = CscMatrix<T>;
+
+    fn sub(self, other: &CscMatrix<T>) -> CscMatrix<T> {
+        self.try_sub(other).unwrap_or_else(|error| panic!("{}", error))
+    }
+}
+
+impl<T: Scalar> Mul<T> for &CscMatrix<T> {
+    type Output = CscMatrix<T>;
+
+    fn mul(self, other: T) -> CscMatrix<T> {
+        let mut result = self.clone();
+
+        for value in result.values.iter_mut() {
+            *value = value.clone() * other.clone();
+        }
+
+        result
+    }
+}
+
+impl Mul<&CscMatrix> for f64 {
+    type Output = CscMatrix;
+
+    fn mul(self, other: &CscMatrix) -> CscMatrix {
+        other * self
+    }
+}
+
+impl<T: Scalar> Mul<&Vector<T>> for &CsrMatrix<T> {
+    type Output = Vector<T>;
+
+    fn mul(self, other: &Vector<T>) -> Vector<T> {
+        self.try_mul_vector(other).unwrap_or_else(|error| panic!("{}", error))
+    }
+}
+
+impl<T: Scalar> Mul<&Vector<T>> for &CscMatrix<T> {
+    type Output = Vector<T>;
+
+    fn mul(self, other: &Vector<T>) -> Vector<T> {
+        self.try_mul_vector(other).unwrap_or_else(|error| panic!("{}", error))
+    }
+}
+
+impl LinearOperator for CsrMatrix {
+    fn dimension(&self) -> (usize, usize) {
+        CsrMatrix::dimension(self)
+    }
+
+    fn apply(&self, x: &Vector) -> Vector {
+        self * x
+    }
+
+    fn transpose_apply(&self, x: &Vector) -> Option<Vector> {
+        check_operand(x, self.rows);
+
+        let mut result = vec![0.0; self.columns];
+
+        for i in 0..self.rows {
+            for k in self.row_pointers[i]..self.row_pointers[i + 1] {
+                result[self.column_indices[k]] += self.values[k] * x.components[i];
+            }
+        }
+
+        Some(Vector::create_with_vec(result))
+    }
+
+    fn diagonal(&self) -> Option<Vector> {
+        Some(CsrMatrix::diagonal(self))
+    }
+
+    fn row(&self, i: usize) -> Option<Vec<(usize, f64)>> {
+        check_row(i, CsrMatrix::dimension(self));
+
+        let entries = (self.row_pointers[i - 1]..self.row_pointers[i])
+                      .map(|k| (self.column_indices[k] + 1, self.values[k]))
+                      .collect();
+
+        Some(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Matrix {
+        Matrix::new(&[&[1.0, 0.0, 2.0, 0.0],
+                      &[0.0, 0.0, 3.0, 0.0],
+                      &[4.0, 5.0, 0.0, 6.0]])
+    }
+
+    #[test]
+    fn csc_elements() {
+        let dense = example();
+        let csc = CscMatrix::from_dense(&dense);
+
+        for i in 1..=3 {
+            for j in 1..=4 {
+                assert_eq!(csc.element(i, j), dense.element(i, j).unwrap());
+            }
+        }
+
+        assert!(csc.try_element(4, 1).is_err());
+        assert!(csc.try_element(1, 0).is_err());
+    }
+
+    #[test]
+    fn csc_arithmetic_matches_dense() {
+        let a = example();
+        let b = Matrix::new(&[&[0.0, 1.0, -2.0, 0.0],
+                              &[0.0, 0.0, 0.0, 7.0],
+                              &[-4.0, 0.0, 1.0, 0.0]]);
+        let (sa, sb) = (CscMatrix::from_dense(&a), CscMatrix::from_dense(&b));
+
+        assert_eq!((&sa + &sb).to_dense(), &a + &b);
+        assert_eq!((&sa - &sb).to_dense(), &a - &b);
+        assert_eq!((2.0 * &sa).to_dense(), &a * 2.0);
+        assert_eq!((&sa + &sb).to_csr(), &CsrMatrix::from_dense(&a) + &CsrMatrix::from_dense(&b));
+        assert_eq!(sa.transpose().to_dense(), a.transpose());
+        assert!(sa.try_add(&sa.transpose()).is_err());
+    }
+
+    fn max_difference(x: &Vector, y: &Vector) -> f64 {
+        x.components.iter()
+         .zip(y.components.iter())
+         .map(|(a, b)| (a - b).abs())
+         .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn coo_sums_duplicates() {
+        let mut coo = CooMatrix::new(3, 4);
+
+        // pushed out of order, with (1, 3) and (3, 1) split in two
+        coo.push(3, 4, 6.0);
+        coo.push(1, 3, 0.5);
+        coo.push(3, 1, 1.0);
+        coo.push(2, 3, 3.0);
+        coo.push(1, 1, 1.0);
+        coo.push(3, 2, 5.0);
+        coo.push(1, 3, 1.5);
+        coo.push(3, 1, 3.0);
+
+        assert_eq!(coo.nnz(), 8);
+        assert_eq!(coo.to_dense(), example());
+
+        let csr = coo.to_csr();
+        let csc = coo.to_csc();
+
+        assert_eq!(csr.nnz(), 6);
+        assert_eq!(csc.nnz(), 6);
+        assert_eq!(csr, CsrMatrix::from_dense(&example()));
+        assert_eq!(csc.to_dense(), example());
+        assert_eq!(csc.to_csr(), csr);
+        assert_eq!(csr.to_csc().to_dense(), example());
+        assert_eq!(csr.to_coo().to_csr(), csr);
+
+        assert!(coo.try_push(4, 1, 1.0).is_err());
+        assert!(coo.try_push(1, 0, 1.0).is_err());
+    }
+
+    #[test]
+    fn csr_products_and_transpose() {
+        let dense = example();
+        let csr = CsrMatrix::from_dense(&dense);
+        let x = Vector::new(&[1.0, -2.0, 0.5, 3.0]);
+        let y = Vector::new(&[2.0, -1.0, 1.0]);
+
+        assert_eq!(&csr * &x, &dense * &x);
+        assert_eq!(&CscMatrix::from_dense(&dense) * &x, &dense * &x);
+        assert_eq!(csr.apply(&x), &dense * &x);
+        assert_eq!(csr.transpose_apply(&y), Some(&dense.transpose() * &y));
+        assert_eq!(csr.transpose().to_dense(), dense.transpose());
+        assert_eq!(csr.transpose().transpose(), csr);
+        assert_eq!(csr.diagonal(), Vector::new(&[1.0, 0.0, 0.0]));
+        assert_eq!(LinearOperator::row(&csr, 3), Some(vec![(1, 4.0), (2, 5.0), (4, 6.0)]));
+        assert_eq!(csr.try_mul_vector(&y),
+                   Err(LinalgError::DimensionMismatch { expected: (1, 4), found: (1, 3) }));
+        assert_eq!(CsrMatrix::<f64>::identity(3).to_dense(), Matrix::identity(3));
+    }
+
+    #[test]
+    fn cancelling_entries_are_dropped() {
+        let a = CsrMatrix::from_dense(&example());
+        let b = CsrMatrix::from_dense(&Matrix::new(&[&[-1.0, 0.0, 0.0, 0.0],
+                                                     &[0.0, 0.0, 0.0, 1.0],
+                                                     &[0.0, 5.0, 0.0, 0.0]]));
+
+        let difference = &a - &a;
+
+        assert_eq!(difference, CsrMatrix::zero(3, 4));
+        assert_eq!(difference.nnz(), 0);
+
+        // (1, 1) cancels, (2, 4) is new and (3, 2) doubles
+        let sum = &a + &b;
+
+        assert_eq!(sum.nnz(), 6);
+        assert_eq!(sum, CsrMatrix::from_dense(&(&example() + &b.to_dense())));
+
+        let csc = a.to_csc();
+
+        assert_eq!((&csc - &csc).nnz(), 0);
+        assert_eq!((&csc + &b.to_csc()).to_csr(), sum);
+    }
+
+    #[test]
+    fn stationary_methods_on_sparse_matrices() {
+        // the 1d laplacian plus 2 I, diagonally dominant
+        let n = 50;
+        let mut coo = CooMatrix::new(n, n);
+
+        for i in 1..=n {
+            coo.push(i, i, 4.0);
+
+            if i > 1 {
+                coo.push(i, i - 1, -1.0);
+                coo.push(i - 1, i, -1.0);
+            }
+        }
+
+        let a = coo.to_csr();
+        let x = Vector::create_with_vec((1..=n).map(|i| (i as f64).sin()).collect());
+        let b = &a * &x;
+        let solver = Solver::default();
+
+        let jacobi = solver.jacobi(&a, &b, 1e-12, 500);
+        let gauss_seidel = solver.gauss_seidel(&a, &b, 1e-12, 500);
+
+        for result in [&jacobi, &gauss_seidel] {
+            assert!(result.converged());
+            assert_eq!(result.diagonally_dominant(), Some(true));
+            assert!(max_difference(&result.solution(), &x) < 1e-10);
+        }
+
+        assert!(gauss_seidel.iterations() < jacobi.iterations());
+
+        // the same iterates as the dense matrix
+        let dense = solver.gauss_seidel(&a.to_dense(), &b, 1e-12, 500);
+
+        assert_eq!(dense.iterations(), gauss_seidel.iterations());
+        assert!(max_difference(&dense.solution(), &gauss_seidel.solution()) < 1e-14);
+    }
+}
@@ -0,0 +1,332 @@
+use crate::*;
+
+// shape of a square matrix as seen by Solver::solve, from its exact zeros
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MatrixStructure {
+    Diagonal,
+    LowerTriangular,
+    UpperTriangular,
+    // only reported when the band is narrow compared to the size
+    Banded {
+        lower: usize,
+        upper: usize,
+    },
+    // symmetric, for real matrices
+    Hermitian,
+    General,
+}
+
+impl<T: Scalar> Matrix<T> {
+    // largest i - j and j - i over the nonzero entries
+    pub fn bandwidths(&self) -> (usize, usize) {
+        let (mut lower, mut upper) = (0, 0);
+
+        for (i, row) in self.data.chunks_exact(self.columns.max(1)).enumerate() {
+            for (j, a) in row.iter().enumerate() {
+                if !a.is_zero() {
+                    lower = lower.max(i.saturating_sub(j));
+                    upper = upper.max(j.saturating_sub(i));
+                }
+            }
+        }
+
+        (lower, upper)
+    }
+
+    pub fn structure(&self) -> MatrixStructure {
+        if self.rows != self.columns {
+            return MatrixStructure::General;
+        }
+
+        match self.bandwidths() {
+            (0, 0) => MatrixStructure::Diagonal,
+            (_, 0) => MatrixStructure::LowerTriangular,
+            (0, _) => MatrixStructure::UpperTriangular,
+            (lower, upper) if 4 * (lower + upper) < self.rows => {
+                MatrixStructure::Banded { lower, upper }
+            }
+            _ if self.is_hermitian(0.0) => MatrixStructure::Hermitian,
+            _ => MatrixStructure::General,
+        }
+    }
+}
+
+fn max_modulus<T: Scalar>(values: &[T]) -> f64 {
+    values.iter().map(|x| x.modulus()).fold(0.0, f64::max)
+}
+
+fn diagonal_solve<T: Scalar>(matrix: &Matrix<T>, b: &Vector<T>) -> Result<Vector<T>, LinalgError> {
+    let n = matrix.rows;
+    let mut x = Vec::with_capacity(n);
+
+    for (i, bi) in b.components.iter().enumerate() {
+        let aii = &matrix.data[i * n + i];
+
+        if aii.is_zero() {
+            return Err(LinalgError::Singular);
+        }
+
+        x.push(bi.clone() / aii.clone());
+    }
+
+    Ok(Vector::create_with_vec(x))
+}
+
+fn banded_solve<T: Scalar>
+(
+    matrix: &Matrix<T>,
+    b: &Vector<T>,
+    lower: usize,
+    upper: usize,
+) -> Result<Vector<T>, LinalgError>
+{
+    // gaussian elimination with partial pivoting that stays inside the
+    // band, O(n lower (lower + upper)). row swaps can widen the upper
+    // band of U up to lower + upper
+    let n = matrix.rows;
+    let width = lower + upper;
+    let mut a = matrix.data.clone();
+    let mut x = b.components.clone();
+
+    for k in 0..n {
+        let last_row = min(k + lower, n - 1);
+        let last_column = min(k + width, n - 1);
+        let pivot = (k..=last_row)
+                    .map(|i| (i, a[i * n + k].modulus()))
+                    .fold((k, -1.0), |best, x| if x.1 > best.1 { x } else { best })
+                    .0;
+
+        if a[pivot * n + k].is_zero() {
+            return Err(LinalgError::Singular);
+        }
+
+        if pivot != k {
+            for j in k..=last_column {
+                a.swap(k * n + j, pivot * n + j);
+            }
+
+            x.swap(k, pivot);
+        }
+
+        for i in (k + 1)..=last_row {
+            let factor = a[i * n + k].clone() / a[k * n + k].clone();
+
+            if factor.is_zero() {
+                continue;
+            }
+
+            for j in (k + 1)..=last_column {
+                a[i * n + j] = a[i * n + j].clone() - factor.clone() * a[k * n + j].clone();
+            }
+
+            x[i] = x[i].clone() - factor * x[k].clone();
+        }
+    }
+
+    for k in (0..n).rev() {
+        let sum = ((k + 1)..=min(k + width, n - 1))
+                  .fold(T::zero(), |sum, j| sum + a[k * n + j].clone() * x[j].clone());
+
+        x[k] = (x[k].clone() - sum) / a[k * n + k].clone();
+    }
+
+    Ok(Vector::create_with_vec(x))
+}
+
+// d is real and positive. for hermitian input the pivots are real, so
+// that means closer to |d| than to -|d|
+fn is_positive<T: Scalar>(d: &T) -> bool {
+    let modulus = d.modulus();
+
+    modulus > 0.0 && (d.clone() - T::from_f64(modulus)).modulus() < modulus
+}
+
+fn cholesky_solve<T: Scalar>
+(
+    matrix: &Matrix<T>,
+    b: &Vector<T>,
+    bandwidth: usize,
+) -> Result<Vector<T>, LinalgError>
+{
+    // square root free cholesky A = L D L^H with unit lower L, so it works
+    // for any scalar type. L keeps the lower bandwidth of A. a pivot of D
+    // that is not positive means A is not positive definite, and then the
+    // caller moves on to pivoted lu
+    if !matrix.is_hermitian(0.0) {
+        return Err(LinalgError::NotSymmetric);
+    }
+
+    let n = matrix.rows;
+    let a = &matrix.data;
+    let mut l = vec![T::zero(); n * n];
+    let mut d: Vec<T> = Vec::with_capacity(n);
+
+    for j in 0..n {
+        let first = j.saturating_sub(bandwidth);
+        let dj = (first..j).fold(a[j * n + j].clone(), |dj, k| {
+            dj - l[j * n + k].clone() * l[j * n + k].conjugate() * d[k].clone()
+        });
+
+        if !is_positive(&dj) {
+            return Err(LinalgError::NotPositiveDefinite { pivot: j + 1 });
+        }
+
+        for i in (j + 1)..=min(j + bandwidth, n - 1) {
+            let lij = (i.saturating_sub(bandwidth)..j).fold(a[i * n + j].clone(), |s, k| {
+                s - l[i * n + k].clone() * l[j * n + k].conjugate() * d[k].clone()
+            });
+
+            l[i * n + j] = lij / dj.clone();
+        }
+
+        d.push(dj);
+    }
+
+    // L y = b, D z = y and L^H x = z, in place
+    let mut x = b.components.clone();
+
+    for i in 0..n {
+        let sum = (i.saturating_sub(bandwidth)..i)
+                  .fold(T::zero(), |sum, k| sum + l[i * n + k].clone() * x[k].clone());
+        x[i] = x[i].clone() - sum;
+    }
+
+    for i in 0..n {
+        x[i] = x[i].clone() / d[i].clone();
+    }
+
+    for i in (0..n).rev() {
+        let sum = ((i + 1)..=min(i + bandwidth, n - 1)).fold(T::zero(), |sum, k| {
+            sum + l[k * n + i].conjugate() * x[k].clone()
+        });
+        x[i] = x[i].clone() - sum;
+    }
+
+    Ok(Vector::create_with_vec(x))
+}
+
+impl Solver {
+    pub(crate) fn structured_solve<T: Scalar>
+    (
+        &self,
+        matrix: &Matrix<T>,
+        b: &Vector<T>,
+    ) -> Result<Vector<T>, LinalgError>
+    {
+        // the method is chosen from the structure of the matrix. whenever
+        // it fails or its solution doesn't pass the residual check, the next
+        // more general one is tried: pivoted lu and then the rref of [A | b],
+        // which also handles singular and non square systems. hermitian
+        // matrices, banded or not, go through cholesky, whose failure means
+        // they aren't positive definite
+        let specialized = match matrix.structure() {
+            MatrixStructure::Diagonal => Some(diagonal_solve(matrix, b)),
+            MatrixStructure::LowerTriangular => Some(self.try_foward_substitution(matrix, b)),
+            MatrixStructure::UpperTriangular => Some(self.try_backward_substitution(matrix, b)),
+            MatrixStructure::Banded { lower, upper } => {
+                let cholesky = if lower == upper {
+                    cholesky_solve(matrix, b, lower)
+                } else {
+                    Err(LinalgError::NotSymmetric)
+                };
+
+                Some(cholesky.or_else(|_| banded_solve(matrix, b, lower, upper)))
+            }
+            MatrixStructure::Hermitian => Some(cholesky_solve(matrix, b, matrix.rows)),
+            MatrixStructure::General => None,
+        };
+
+        if let Some(Ok(x)) = specialized {
+            if self.has_small_residual(matrix, &x, b) {
+                return Ok(x);
+            }
+        }
+
+        if matrix.rows == matrix.columns {
+            let lu = LuDecomposition::new(matrix);
+
+            if !lu.is_singular() {
+                let x = lu.try_solve(b)?;
+
+                if self.has_small_residual(matrix, &x, b) {
+                    return Ok(x);
+                }
+            }
+        }
+
+        let x = self.try_solve_with_pivoting(matrix, b, &Eliminator::new())?;
+
+        if !self.has_small_residual(matrix, &x, b) {
+            return Err(LinalgError::NoSolution);
+        }
+
+        Ok(x)
+    }
+
+    fn has_small_residual<T: Scalar>(&self, matrix: &Matrix<T>, x: &Vector<T>, b: &Vector<T>) -> bool {
+        // |b - A x| <= sqrt(eps) (|A| |x| + |b|) in the infinity norms.
+        // exact types need a zero residual
+        let residual = b - &(matrix * x);
+        let a_norm = matrix.data.chunks_exact(matrix.columns.max(1))
+                     .map(|row| row.iter().map(|a| a.modulus()).sum::<f64>())
+                     .fold(0.0, f64::max);
+        let bound = T::epsilon().sqrt()
+                    * (a_norm * max_modulus(&x.components) + max_modulus(&b.components));
+
+        max_modulus(&residual.components) <= bound
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rational::Rational;
+
+    fn residual(matrix: &Matrix, x: &Vector, b: &Vector) -> f64 {
+        max_modulus(&(b - &(matrix * x)).components)
+    }
+
+    #[test]
+    fn positive_definite_banded_system_uses_cholesky() {
+        let n = 12;
+        let mut a = Matrix::zero(n, n);
+
+        for i in 1..=n {
+            a.change_element(i, i, 4.0);
+
+            if i < n {
+                a.change_element(i, i + 1, -1.0);
+                a.change_element(i + 1, i, -1.0);
+            }
+        }
+
+        let b = Vector::create_with_vec((0..n).map(|i| i as f64).collect());
+
+        assert_eq!(a.structure(), MatrixStructure::Banded { lower: 1, upper: 1 });
+
+        let x = cholesky_solve(&a, &b, 1).unwrap();
+
+        assert!(residual(&a, &x, &b) < 1e-14);
+        assert!(residual(&a, &Solver::new().solve(&a, &b), &b) < 1e-14);
+    }
+
+    #[test]
+    fn indefinite_hermitian_system_falls_back_to_lu() {
+        let a = Matrix::new(&[&[1.0, 2.0, 3.0], &[2.0, 1.0, 4.0], &[3.0, 4.0, 1.0]]);
+        let b = Vector::new(&[1.0, 2.0, 3.0]);
+
+        assert_eq!(a.structure(), MatrixStructure::Hermitian);
+        assert_eq!(cholesky_solve(&a, &b, 3), Err(LinalgError::NotPositiveDefinite { pivot: 2 }));
+        assert!(residual(&a, &Solver::new().solve(&a, &b), &b) < 1e-14);
+    }
+
+    #[test]
+    fn exact_cholesky() {
+        let r = |p: i64, q: i64| Rational::new(p, q);
+        let a = Matrix::new(&[&[r(2, 1), r(1, 2)], &[r(1, 2), r(1, 3)]]);
+        let b = Vector::new(&[r(1, 1), r(0, 1)]);
+        let x = cholesky_solve(&a, &b, 2).unwrap();
+
+        assert_eq!(&a * &x, b);
+    }
+}
@@ -0,0 +1,461 @@
+use crate::*;
+
+const SVD_MAX_SWEEPS: usize = 75;
+
+// (u columns, singular values, v columns)
+type SvdFactors = (Vec<Vec<f64>>, Vec<f64>, Vec<Vec<f64>>);
+
+pub struct Svd {
+    rows: usize,
+    columns: usize,
+    // thin factors stored by columns: u is m x k, v is n x k, k = min(m, n)
+    u: Vec<Vec<f64>>,
+    singular_values: Vec<f64>,
+    v: Vec<Vec<f64>>,
+}
+
+impl Svd {
+    fn new(matrix: &Matrix) -> Result<Self, LinalgError> {
+        let (m, n) = matrix.dimension();
+
+        if m >= n {
+            let (u, singular_values, v) = Self::one_sided_jacobi(matrix)?;
+
+            Ok(Self { rows: m, columns: n, u, singular_values, v })
+        } else {
+            // A^T = U S V^T  =>  A = V S U^T
+            let (u, singular_values, v) = Self::one_sided_jacobi(&matrix.transpose())?;
+
+            Ok(Self { rows: m, columns: n, u: v, singular_values, v: u })
+        }
+    }
+
+    fn one_sided_jacobi(matrix: &Matrix) -> Result<SvdFactors, LinalgError> {
+        // rotates pairs of columns of A V until they are all orthogonal,
+        // then A V = U S with the column norms as singular values. needs m >= n
+        let (m, n) = matrix.dimension();
+        let mut columns = (1..=n)
+                          .map(|j| matrix.get_column(j).unwrap().components)
+                          .collect::<Vec<Vec<f64>>>();
+        let mut v = (0..n)
+                    .map(|j| Vector::canonical(j + 1, n).components)
+                    .collect::<Vec<Vec<f64>>>();
+
+        let mut converged = false;
+
+        for _ in 0..SVD_MAX_SWEEPS {
+            let mut rotated = false;
+
+            for i in 0..n {
+                for j in (i + 1)..n {
+                    let alpha = dot(&columns[i], &columns[i]);
+                    let beta = dot(&columns[j], &columns[j]);
+                    let gamma = dot(&columns[i], &columns[j]);
+
+                    if gamma == 0.0 || gamma.abs() <= f64::EPSILON * (alpha * beta).sqrt() {
+                        continue;
+                    }
+
+                    rotated = true;
+
+                    let zeta = (beta - alpha) / (2.0 * gamma);
+                    let sign = if zeta >= 0.0 { 1.0 } else { -1.0 };
+                    let t = sign / (zeta.abs() + (1.0 + zeta * zeta).sqrt());
+                    let c = 1.0 / (1.0 + t * t).sqrt();
+                    let s = c * t;
+
+                    rotate_pair(&mut columns, i, j, c, s);
+                    rotate_pair(&mut v, i, j, c, s);
+                }
+            }
+
+            if !rotated {
+                converged = true;
+                break;
+            }
+        }
+
+        if !converged {
+            return Err(LinalgError::NoConvergence { iterations: SVD_MAX_SWEEPS });
+        }
+
+        let mut order = (0..n).collect::<Vec<usize>>();
+        let norms = columns.iter().map(|c| dot(c, c).sqrt()).collect::<Vec<f64>>();
+        order.sort_by(|&i, &j| norms[j].total_cmp(&norms[i]));
+
+        let singular_values = order.iter().map(|&j| norms[j]).collect::<Vec<f64>>();
+        let v = order.iter().map(|&j| v[j].clone()).collect::<Vec<Vec<f64>>>();
+
+        // columns belonging to (numerically) zero singular values can't be
+        // normalized, so they are replaced by an orthonormal completion
+        let threshold = singular_values[0] * f64::EPSILON * m as f64;
+        let mut u = vec![];
+
+        for &j in order.iter() {
+            if norms[j] > threshold {
+                u.push(columns[j].iter().map(|x| x / norms[j]).collect::<Vec<f64>>());
+            }
+        }
+
+        let missing = n - u.len();
+        u.extend(orthonormal_completion(&u, m, missing));
+
+        Ok((u, singular_values, v))
+    }
+
+    pub fn singular_values(&self) -> Vector {
+        // in descending order
+        Vector::create_with_vec(self.singular_values.clone())
+    }
+
+    pub fn u(&self) -> Matrix {
+        columns_to_matrix(&self.u, self.rows)
+    }
+
+    pub fn vt(&self) -> Matrix {
+        columns_to_matrix(&self.v, self.columns).transpose()
+    }
+
+    pub fn sigma(&self) -> Matrix {
+        let k = self.singular_values.len();
+        let mut sigma = Matrix::zero(k, k);
+
+        for (i, &value) in self.singular_values.iter().enumerate() {
+            sigma.data[i * k + i] = value;
+        }
+
+        sigma
+    }
+
+    pub fn full_u(&self) -> Matrix {
+        let mut columns = self.u.clone();
+        columns.extend(orthonormal_completion(&self.u, self.rows, self.rows - self.u.len()));
+
+        columns_to_matrix(&columns, self.rows)
+    }
+
+    pub fn full_vt(&self) -> Matrix {
+        let mut columns = self.v.clone();
+        columns.extend(orthonormal_completion(&self.v, self.columns,
+                                              self.columns - self.v.len()));
+
+        columns_to_matrix(&columns, self.columns).transpose()
+    }
+
+    pub fn full_sigma(&self) -> Matrix {
+        let mut sigma = Matrix::zero(self.rows, self.columns);
+
+        for (i, &value) in self.singular_values.iter().enumerate() {
+            sigma.data[i * self.columns + i] = value;
+        }
+
+        sigma
+    }
+
+    pub fn rank(&self, tolerance: f64) -> usize {
+        self.singular_values.iter().filter(|&&s| s > tolerance).count()
+    }
+
+    pub fn condition_number(&self) -> f64 {
+        // 2-norm condition number, infinite for rank deficient matrices.
+        // a smallest singular value at rounding level counts as zero
+        let max = self.singular_values[0];
+        let min = self.singular_values[self.singular_values.len() - 1];
+
+        if min <= max * f64::EPSILON * self.rows.max(self.columns) as f64 {
+            return f64::INFINITY;
+        }
+
+        max / min
+    }
+
+    pub fn pseudo_inverse(&self, tolerance: f64) -> Matrix {
+        // V S^+ U^T, singular values below the tolerance are treated as zero
+        let (m, n) = (self.rows, self.columns);
+        let mut result = Matrix::zero(n, m);
+
+        for (k, &sigma) in self.singular_values.iter().enumerate() {
+            if sigma <= tolerance {
+                continue;
+            }
+
+            for i in 0..n {
+                let factor = self.v[k][i] / sigma;
+
+                for j in 0..m {
+                    result.data[i * m + j] += factor * self.u[k][j];
+                }
+            }
+        }
+
+        result
+    }
+
+    pub fn solve(&self, b: &Vector, tolerance: f64) -> Vector {
+        self.try_solve(b, tolerance).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_solve(&self, b: &Vector, tolerance: f64) -> Result<Vector, LinalgError> {
+        // minimum norm least squares solution, also for rank deficient systems
+        if b.size() != self.rows {
+            return Err(LinalgError::DimensionMismatch {
+                expected: (1, self.rows),
+                found: b.dimension(),
+            });
+        }
+
+        let mut x = vec![0.0; self.columns];
+
+        for (k, &sigma) in self.singular_values.iter().enumerate() {
+            if sigma <= tolerance {
+                continue;
+            }
+
+            let coefficient = dot(&self.u[k], &b.components) / sigma;
+
+            for (xi, vi) in x.iter_mut().zip(self.v[k].iter()) {
+                *xi += coefficient * vi;
+            }
+        }
+
+        Ok(Vector::create_with_vec(x))
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+fn rotate_pair(columns: &mut [Vec<f64>], i: usize, j: usize, c: f64, s: f64) {
+    for k in 0..columns[i].len() {
+        let a = columns[i][k];
+        let b = columns[j][k];
+        columns[i][k] = c * a - s * b;
+        columns[j][k] = s * a + c * b;
+    }
+}
+
+fn columns_to_matrix(columns: &[Vec<f64>], rows: usize) -> Matrix {
+    let mut matrix = Matrix::zero(rows, columns.len());
+
+    for (j, column) in columns.iter().enumerate() {
+        for (i, &value) in column.iter().enumerate() {
+            matrix.data[i * columns.len() + j] = value;
+        }
+    }
+
+    matrix
+}
+
+fn orthonormal_completion(basis: &[Vec<f64>], size: usize, count: usize) -> Vec<Vec<f64>> {
+    // extends an orthonormal set with canonical vectors, orthogonalized
+    // twice with modified gram schmidt, picking the largest remainder
+    let mut all = basis.to_vec();
+    let mut extra = vec![];
+
+    while extra.len() < count {
+        let mut best: Option<Vec<f64>> = None;
+        let mut best_norm = 0.0;
+
+        for e in 0..size {
+            let mut candidate = vec![0.0; size];
+            candidate[e] = 1.0;
+
+            for _ in 0..2 {
+                for q in all.iter() {
+                    let projection = dot(q, &candidate);
+
+                    for (c, qi) in candidate.iter_mut().zip(q.iter()) {
+                        *c -= projection * qi;
+                    }
+                }
+            }
+
+            let norm = dot(&candidate, &candidate).sqrt();
+
+            if norm > best_norm {
+                best_norm = norm;
+                best = Some(candidate);
+            }
+        }
+
+        let vector = best.unwrap()
+                     .into_iter()
+                     .map(|x| x / best_norm)
+                     .collect::<Vec<f64>>();
+
+        all.push(vector.clone());
+        extra.push(vector);
+    }
+
+    extra
+}
+
+impl Decomposer {
+    pub fn svd(&self, matrix: &Matrix) -> Svd {
+        self.try_svd(matrix).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_svd(&self, matrix: &Matrix) -> Result<Svd, LinalgError> {
+        // A = U S V^T through one sided jacobi rotations
+        Svd::new(matrix)
+    }
+}
+
+impl Matrix {
+    pub fn pseudo_inverse(&self, tolerance: f64) -> Matrix {
+        Decomposer::new().svd(self).pseudo_inverse(tolerance)
+    }
+
+    pub fn rank(&self, tolerance: f64) -> usize {
+        Decomposer::new().svd(self).rank(tolerance)
+    }
+
+    pub fn condition_number(&self) -> f64 {
+        Decomposer::new().svd(self).condition_number()
+    }
+}
+
+impl LeastSquares {
+    pub fn via_svd(&self, matrix: &Matrix, b: &Vector, tolerance: f64) -> Vector {
+        self.try_via_svd(matrix, b, tolerance).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_via_svd
+    (
+        &self,
+        matrix: &Matrix,
+        b: &Vector,
+        tolerance: f64
+    ) -> Result<Vector, LinalgError>
+    {
+        // minimum norm least squares solution, works without full column rank
+        Decomposer::new().try_svd(matrix)?.try_solve(b, tolerance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn max_difference(a: &Matrix, b: &Matrix) -> f64 {
+        assert_eq!(a.dimension(), b.dimension());
+        a.data.iter().zip(b.data.iter()).map(|(x, y)| (x - y).abs()).fold(0.0, f64::max)
+    }
+
+    fn check_svd(a: &Matrix) -> Svd {
+        let (m, n) = a.dimension();
+        let k = m.min(n);
+        let svd = Decomposer::new().svd(a);
+        let values = svd.singular_values().components;
+
+        assert_eq!(values.len(), k);
+        assert!(values.iter().all(|&s| s >= 0.0));
+        assert!(values.windows(2).all(|pair| pair[0] >= pair[1]));
+
+        let (u, vt) = (svd.u(), svd.vt());
+        let (full_u, full_vt) = (svd.full_u(), svd.full_vt());
+
+        assert!(max_difference(&(&u.transpose() * &u), &Matrix::identity(k)) < 1e-13);
+        assert!(max_difference(&(&vt * &vt.transpose()), &Matrix::identity(k)) < 1e-13);
+        assert!(max_difference(&(&full_u.transpose() * &full_u), &Matrix::identity(m)) < 1e-13);
+        assert!(max_difference(&(&full_vt * &full_vt.transpose()), &Matrix::identity(n)) < 1e-13);
+
+        assert!(max_difference(&(&(&u * &svd.sigma()) * &vt), a) < 1e-12);
+        assert!(max_difference(&(&(&full_u * &svd.full_sigma()) * &full_vt), a) < 1e-12);
+
+        svd
+    }
+
+    fn tall() -> Matrix {
+        Matrix::new(&[&[2.0, -1.0, 0.5],
+                      &[1.0, 3.0, -2.0],
+                      &[0.0, 1.0, 4.0],
+                      &[-1.0, 2.0, 1.0]])
+    }
+
+    // third column = first + second
+    fn rank_deficient() -> Matrix {
+        Matrix::new(&[&[1.0, 2.0, 3.0],
+                      &[4.0, 5.0, 9.0],
+                      &[7.0, 8.0, 15.0],
+                      &[1.0, 0.0, 1.0]])
+    }
+
+    #[test]
+    fn tall_and_wide_factorizations() {
+        let svd = check_svd(&tall());
+        let wide = check_svd(&tall().transpose());
+
+        assert_eq!(svd.rank(1e-10), 3);
+        assert_eq!(svd.singular_values(), wide.singular_values());
+
+        let diagonal = check_svd(&Matrix::new(&[&[1.0, 0.0], &[0.0, -3.0]]));
+
+        assert_eq!(diagonal.singular_values().components, vec![3.0, 1.0]);
+        assert_eq!(diagonal.condition_number(), 3.0);
+    }
+
+    #[test]
+    fn rank_deficient_factorizations() {
+        let a = rank_deficient();
+        let svd = check_svd(&a);
+
+        assert!(svd.singular_values().components[2] < 1e-12);
+        assert_eq!(svd.rank(1e-10), 2);
+        assert_eq!(a.rank(1e-10), 2);
+        assert_eq!(a.transpose().rank(1e-10), 2);
+
+        check_svd(&a.transpose());
+        check_svd(&Matrix::zero(3, 2));
+    }
+
+    #[test]
+    fn singular_matrices_have_infinite_condition_number() {
+        assert_eq!(Matrix::new(&[&[1.0, 2.0], &[2.0, 4.0]]).condition_number(), f64::INFINITY);
+        assert_eq!(rank_deficient().condition_number(), f64::INFINITY);
+        assert_eq!(Matrix::zero(2, 2).condition_number(), f64::INFINITY);
+        assert!(tall().condition_number().is_finite());
+    }
+
+    #[test]
+    fn pseudo_inverse_satisfies_the_penrose_conditions() {
+        for a in [tall(), tall().transpose(), rank_deficient(), rank_deficient().transpose()] {
+            let pinv = a.pseudo_inverse(1e-10);
+
+            assert_eq!(pinv.dimension(), a.transpose().dimension());
+            assert!(max_difference(&(&(&a * &pinv) * &a), &a) < 1e-12);
+            assert!(max_difference(&(&(&pinv * &a) * &pinv), &pinv) < 1e-12);
+
+            let (ap, pa) = (&a * &pinv, &pinv * &a);
+
+            assert!(max_difference(&ap, &ap.transpose()) < 1e-12);
+            assert!(max_difference(&pa, &pa.transpose()) < 1e-12);
+        }
+
+        // full column rank: A^+ = (A^T A)^-1 A^T
+        let a = tall();
+        let normal = &(&a.transpose() * &a).inverse() * &a.transpose();
+
+        assert!(max_difference(&a.pseudo_inverse(1e-10), &normal) < 1e-12);
+    }
+
+    #[test]
+    fn minimum_norm_solutions() {
+        let a = tall();
+        let b = Vector::new(&[1.0, 2.0, 3.0, 4.0]);
+        let x = LeastSquares::new().via_svd(&a, &b, 1e-10);
+        let expected = &(&(&a.transpose() * &a).inverse() * &a.transpose()) * &b;
+
+        assert!((&x - &expected).components.iter().all(|d| d.abs() < 1e-12));
+
+        // every x with x1 + x2 = 2 solves it, the minimum norm one is (1, 1)
+        let a = Matrix::new(&[&[1.0, 1.0], &[1.0, 1.0]]);
+        let x = Decomposer::new().svd(&a).solve(&Vector::new(&[2.0, 2.0]), 1e-10);
+
+        assert!((&x - &Vector::new(&[1.0, 1.0])).components.iter().all(|d| d.abs() < 1e-14));
+
+        assert_eq!(Decomposer::new().svd(&a).try_solve(&Vector::new(&[1.0, 2.0, 3.0]), 0.0),
+                   Err(LinalgError::DimensionMismatch { expected: (1, 2), found: (1, 3) }));
+    }
+}
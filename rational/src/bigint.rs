@@ -0,0 +1,504 @@
+use std::cmp::Ordering;
+use std::ops::{Add, Sub, Mul, Neg};
+use std::fmt;
+use crate::LinalgError;
+
+// arbitrary precision integer as sign and magnitude. the magnitude holds
+// base 2^32 digits, least significant first and without leading zeros,
+// so zero is the empty vector and is never negative
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    digits: Vec<u32>,
+}
+
+fn trim(mut digits: Vec<u32>) -> Vec<u32> {
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+
+    digits
+}
+
+fn compare_magnitudes(a: &[u32], b: &[u32]) -> Ordering {
+    a.len().cmp(&b.len())
+     .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut result = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0;
+
+    for (i, &x) in long.iter().enumerate() {
+        let sum = x as u64 + *short.get(i).unwrap_or(&0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+
+    result
+}
+
+// a - b, with a >= b
+fn sub_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+
+    for (i, &x) in a.iter().enumerate() {
+        let difference = x as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        result.push(difference as u32);
+        borrow = (difference < 0) as i64;
+    }
+
+    trim(result)
+}
+
+fn mul_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+
+    let mut result = vec![0; a.len() + b.len()];
+
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0;
+
+        for (j, &y) in b.iter().enumerate() {
+            let product = x as u64 * y as u64 + result[i + j] as u64 + carry;
+            result[i + j] = product as u32;
+            carry = product >> 32;
+        }
+
+        result[i + b.len()] = carry as u32;
+    }
+
+    trim(result)
+}
+
+fn shift_left(a: &[u32], bits: u32) -> Vec<u32> {
+    // bits < 32, always returns a.len() + 1 digits
+    let mut result = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0;
+
+    for &x in a.iter() {
+        result.push((x << bits) | carry);
+        carry = if bits == 0 { 0 } else { x >> (32 - bits) };
+    }
+
+    result.push(carry);
+    result
+}
+
+fn shift_right(a: &[u32], bits: u32) -> Vec<u32> {
+    // bits < 32
+    let mut result = vec![0; a.len()];
+
+    for i in 0..a.len() {
+        let high = if bits == 0 { 0 } else { *a.get(i + 1).unwrap_or(&0) << (32 - bits) };
+        result[i] = (a[i] >> bits) | high;
+    }
+
+    trim(result)
+}
+
+fn div_rem_digit(a: &[u32], divisor: u32) -> (Vec<u32>, u32) {
+    let mut quotient = vec![0; a.len()];
+    let mut remainder = 0u64;
+
+    for i in (0..a.len()).rev() {
+        let current = (remainder << 32) | a[i] as u64;
+        quotient[i] = (current / divisor as u64) as u32;
+        remainder = current % divisor as u64;
+    }
+
+    (trim(quotient), remainder as u32)
+}
+
+// long division (knuth's algorithm d), b is not zero
+fn div_rem_magnitudes(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if compare_magnitudes(a, b) == Ordering::Less {
+        return (vec![], a.to_vec());
+    }
+
+    if b.len() == 1 {
+        let (quotient, remainder) = div_rem_digit(a, b[0]);
+        return (quotient, trim(vec![remainder]));
+    }
+
+    // normalize so that the top digit of the divisor has its high bit
+    // set, then every estimate of a quotient digit is off by at most 2
+    let shift = b[b.len() - 1].leading_zeros();
+    let v = trim(shift_left(b, shift));
+    let mut u = shift_left(a, shift);
+    let n = v.len();
+    let m = a.len() - n;
+    let base = 1u64 << 32;
+    let mut quotient = vec![0; m + 1];
+
+    for j in (0..=m).rev() {
+        let numerator = ((u[j + n] as u64) << 32) | u[j + n - 1] as u64;
+        let mut q = numerator / v[n - 1] as u64;
+        let mut r = numerator % v[n - 1] as u64;
+
+        while q >= base || q * v[n - 2] as u64 > ((r << 32) | u[j + n - 2] as u64) {
+            q -= 1;
+            r += v[n - 1] as u64;
+
+            if r >= base {
+                break;
+            }
+        }
+
+        // u[j..=j + n] -= q v
+        let mut borrow = 0i64;
+        let mut carry = 0u64;
+
+        for i in 0..n {
+            let product = q * v[i] as u64 + carry;
+            carry = product >> 32;
+
+            let difference = u[i + j] as i64 - borrow - (product & 0xffff_ffff) as i64;
+            u[i + j] = difference as u32;
+            borrow = (difference < 0) as i64;
+        }
+
+        let difference = u[j + n] as i64 - borrow - carry as i64;
+        u[j + n] = difference as u32;
+
+        if difference < 0 {
+            // q was one too large, add v back
+            q -= 1;
+            let mut carry = 0u64;
+
+            for i in 0..n {
+                let sum = u[i + j] as u64 + v[i] as u64 + carry;
+                u[i + j] = sum as u32;
+                carry = sum >> 32;
+            }
+
+            u[j + n] = u[j + n].wrapping_add(carry as u32);
+        }
+
+        quotient[j] = q as u32;
+    }
+
+    (trim(quotient), shift_right(&u[..n], shift))
+}
+
+impl BigInt {
+    pub fn zero() -> Self {
+        Self {
+            negative: false,
+            digits: vec![],
+        }
+    }
+
+    fn from_parts(negative: bool, digits: Vec<u32>) -> Self {
+        let digits = trim(digits);
+
+        Self {
+            negative: negative && !digits.is_empty(),
+            digits,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn is_one(&self) -> bool {
+        !self.negative && self.digits == [1]
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::from_parts(false, self.digits.clone())
+    }
+
+    // number of bits of the magnitude, 0 for zero
+    pub fn bits(&self) -> u64 {
+        match self.digits.last() {
+            None => 0,
+            Some(top) => self.digits.len() as u64 * 32 - top.leading_zeros() as u64,
+        }
+    }
+
+    // |self| * 2^bits, keeping the sign
+    pub fn shl(&self, bits: u64) -> Self {
+        let mut digits = vec![0; (bits / 32) as usize];
+        digits.extend(shift_left(&self.digits, (bits % 32) as u32));
+
+        Self::from_parts(self.negative, digits)
+    }
+
+    // quotient rounded towards zero and remainder with the sign of self.
+    // panics if other is zero
+    pub fn div_rem(&self, other: &BigInt) -> (BigInt, BigInt) {
+        self.try_div_rem(other).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_div_rem(&self, other: &BigInt) -> Result<(BigInt, BigInt), LinalgError> {
+        if other.is_zero() {
+            return Err(LinalgError::DivisionByZero);
+        }
+
+        let (quotient, remainder) = div_rem_magnitudes(&self.digits, &other.digits);
+
+        Ok((Self::from_parts(self.negative != other.negative, quotient),
+            Self::from_parts(self.negative, remainder)))
+    }
+
+    // greatest common divisor, always non-negative. gcd(0, 0) = 0
+    pub fn gcd(&self, other: &BigInt) -> BigInt {
+        let mut a = self.digits.clone();
+        let mut b = other.digits.clone();
+
+        while !b.is_empty() {
+            let (_, remainder) = div_rem_magnitudes(&a, &b);
+            a = b;
+            b = remainder;
+        }
+
+        Self::from_parts(false, a)
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self.digits.iter()
+                        .rev()
+                        .fold(0.0, |value, &digit| value * 4294967296.0 + digit as f64);
+
+        if self.negative { -magnitude } else { magnitude }
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        let magnitude = value.unsigned_abs();
+
+        Self::from_parts(value < 0, vec![magnitude as u32, (magnitude >> 32) as u32])
+    }
+}
+
+impl From<u64> for BigInt {
+    fn from(value: u64) -> Self {
+        Self::from_parts(false, vec![value as u32, (value >> 32) as u32])
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_magnitudes(&self.digits, &other.digits),
+            (true, true) => compare_magnitudes(&other.digits, &self.digits),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // groups of 9 decimal digits, least significant first
+        let mut groups = vec![];
+        let mut rest = self.digits.clone();
+
+        while !rest.is_empty() {
+            let (quotient, group) = div_rem_digit(&rest, 1_000_000_000);
+            groups.push(group);
+            rest = quotient;
+        }
+
+        if self.negative {
+            write!(f, "-")?;
+        }
+
+        write!(f, "{}", groups[groups.len() - 1])?;
+
+        for group in groups.iter().rev().skip(1) {
+            write!(f, "{:09}", group)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> Self::Output {
+        if self.negative == other.negative {
+            return BigInt::from_parts(self.negative, add_magnitudes(&self.digits, &other.digits));
+        }
+
+        match compare_magnitudes(&self.digits, &other.digits) {
+            Ordering::Less => {
+                BigInt::from_parts(other.negative, sub_magnitudes(&other.digits, &self.digits))
+            }
+            _ => BigInt::from_parts(self.negative, sub_magnitudes(&self.digits, &other.digits)),
+        }
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> Self::Output {
+        self + &(-other)
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> Self::Output {
+        BigInt::from_parts(self.negative != other.negative,
+                           mul_magnitudes(&self.digits, &other.digits))
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> Self::Output {
+        BigInt::from_parts(!self.negative, self.digits.clone())
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> Self::Output {
+        BigInt::from_parts(!self.negative, self.digits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_digits(negative: bool, digits: &[u32]) -> BigInt {
+        BigInt::from_parts(negative, digits.to_vec())
+    }
+
+    fn check_division(a: &BigInt, b: &BigInt) {
+        let (quotient, remainder) = a.div_rem(b);
+
+        assert_eq!(&(&quotient * b) + &remainder, *a);
+        assert_eq!(compare_magnitudes(&remainder.digits, &b.digits), Ordering::Less);
+        assert!(remainder.is_zero() || remainder.is_negative() == a.is_negative());
+    }
+
+    #[test]
+    fn division_with_add_back() {
+        // the estimate of the first quotient digit is one too large even
+        // after the two digit test, so the divisor is added back
+        let a = from_digits(false, &[0, 0, 0x8000_0000, 0x7fff_ffff]);
+        let b = from_digits(false, &[1, 0, 0x8000_0000]);
+        let (quotient, remainder) = a.div_rem(&b);
+
+        assert_eq!(quotient, from_digits(false, &[0xffff_fffe]));
+        assert_eq!(remainder, from_digits(false, &[2, 0xffff_ffff, 0x7fff_ffff]));
+
+        let a = from_digits(false, &[3, 0, 0x8000_0000]);
+        let b = from_digits(false, &[1, 0, 0x2000_0000]);
+        let (quotient, remainder) = a.div_rem(&b);
+
+        assert_eq!(quotient, BigInt::from(3i64));
+        assert_eq!(remainder, from_digits(false, &[0, 0, 0x2000_0000]));
+    }
+
+    #[test]
+    fn division_with_corrected_estimate() {
+        // the two digit test lowers the estimate of the quotient digit
+        let a = from_digits(false, &[0x8000_0000, 0x8000_0000, 1]);
+        let b = from_digits(false, &[0xffff_ffff, 0x8000_0000]);
+        let (quotient, remainder) = a.div_rem(&b);
+
+        assert_eq!(quotient, BigInt::from(2i64));
+        assert_eq!(remainder, from_digits(false, &[0x8000_0002, 0x7fff_fffe]));
+
+        // the first estimate is the base itself
+        let a = from_digits(false, &[0x7fff_ffff, 0, 0xffff_ffff]);
+        let b = from_digits(false, &[0xffff_ffff, 0xffff_ffff]);
+        let (quotient, remainder) = a.div_rem(&b);
+
+        assert_eq!(quotient, from_digits(false, &[0xffff_ffff]));
+        assert_eq!(remainder, from_digits(false, &[0x7fff_fffe, 1]));
+
+        let a = from_digits(false, &[0x89ab_cdef, 0x0123_4567, 0xfedc_ba98, 0x7654_3210, 0x0f0f_0f0f]);
+        let b = from_digits(false, &[0x1111_1111, 0x2222_2222, 3]);
+        let (quotient, remainder) = a.div_rem(&b);
+
+        assert_eq!(quotient, from_digits(false, &[0x580a_9a40, 0x3635_0c5f, 0x04ce_5682]));
+        assert_eq!(remainder, from_digits(false, &[0x7e79_4faf, 0xa438_94c7]));
+    }
+
+    #[test]
+    fn division_invariants() {
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+
+        for _ in 0..500 {
+            let a_digits = (0..(next() % 6 + 1)).map(|_| next() as u32).collect::<Vec<u32>>();
+            let b_digits = (0..(next() % 4 + 1)).map(|_| (next() >> 32) as u32).collect::<Vec<u32>>();
+            let a = from_digits(next() % 2 == 0, &a_digits);
+            let b = from_digits(next() % 2 == 0, &b_digits);
+
+            if !b.is_zero() {
+                check_division(&a, &b);
+            }
+        }
+    }
+
+    #[test]
+    fn division_signs() {
+        let cases = [(7, 2, 3, 1), (-7, 2, -3, -1), (7, -2, -3, 1), (-7, -2, 3, -1), (0, -5, 0, 0)];
+
+        for (a, b, quotient, remainder) in cases {
+            let (q, r) = BigInt::from(a as i64).div_rem(&BigInt::from(b as i64));
+
+            assert_eq!(q, BigInt::from(quotient as i64));
+            assert_eq!(r, BigInt::from(remainder as i64));
+        }
+
+        assert!(!(-BigInt::zero()).is_negative());
+    }
+
+    #[test]
+    fn division_by_zero() {
+        assert_eq!(BigInt::from(5i64).try_div_rem(&BigInt::zero()), Err(LinalgError::DivisionByZero));
+    }
+
+    #[test]
+    fn gcd_and_display() {
+        let a = BigInt::from(12i64).shl(100);
+        let b = BigInt::from(-18i64).shl(90);
+
+        assert_eq!(a.gcd(&b), BigInt::from(6i64).shl(90));
+        assert_eq!(BigInt::zero().gcd(&BigInt::zero()), BigInt::zero());
+        assert_eq!(BigInt::from(1i64).shl(64).to_string(), "18446744073709551616");
+        assert_eq!(BigInt::from(i64::MIN).to_string(), "-9223372036854775808");
+    }
+}
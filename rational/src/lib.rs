@@ -0,0 +1,377 @@
+#![allow(dead_code, unused_imports, unused_variables,
+         unused_mut, non_snake_case, deprecated)]
+
+use std::ops::{Add, Sub, Mul, Div, Neg};
+use std::cmp::Ordering;
+use std::fmt;
+use vectors::Scalar;
+
+pub use errors::LinalgError;
+
+mod bigint;
+pub use bigint::BigInt;
+
+// exact fraction numerator / denominator. always kept in lowest terms
+// with a positive denominator, so equal numbers have equal fields
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: BigInt,
+    denominator: BigInt,
+}
+
+impl Rational {
+    pub fn new(numerator: i64, denominator: i64) -> Self {
+        Self::try_new(numerator, denominator).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_new(numerator: i64, denominator: i64) -> Result<Self, LinalgError> {
+        Self::try_from_bigints(BigInt::from(numerator), BigInt::from(denominator))
+    }
+
+    pub fn from_integer(value: i64) -> Self {
+        Self::reduced(BigInt::from(value), BigInt::from(1i64))
+    }
+
+    pub fn from_bigints(numerator: BigInt, denominator: BigInt) -> Self {
+        Self::try_from_bigints(numerator, denominator)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_from_bigints(numerator: BigInt, denominator: BigInt) -> Result<Self, LinalgError> {
+        if denominator.is_zero() {
+            return Err(LinalgError::DivisionByZero);
+        }
+
+        Ok(Self::reduced(numerator, denominator))
+    }
+
+    fn reduced(numerator: BigInt, denominator: BigInt) -> Self {
+        let gcd = numerator.gcd(&denominator);
+
+        let (mut numerator, mut denominator) = if gcd.is_zero() || gcd.is_one() {
+            (numerator, denominator)
+        } else {
+            (numerator.div_rem(&gcd).0, denominator.div_rem(&gcd).0)
+        };
+
+        if denominator.is_negative() {
+            numerator = -numerator;
+            denominator = -denominator;
+        }
+
+        Self {
+            numerator,
+            denominator,
+        }
+    }
+
+    pub fn numerator(&self) -> &BigInt {
+        &self.numerator
+    }
+
+    pub fn denominator(&self) -> &BigInt {
+        &self.denominator
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator.is_one()
+    }
+
+    pub fn abs(&self) -> Self {
+        Self {
+            numerator: self.numerator.abs(),
+            denominator: self.denominator.clone(),
+        }
+    }
+
+    pub fn try_recip(&self) -> Result<Self, LinalgError> {
+        Self::try_from_bigints(self.denominator.clone(), self.numerator.clone())
+    }
+
+    pub fn try_div(&self, other: &Rational) -> Result<Self, LinalgError> {
+        if other.numerator.is_zero() {
+            return Err(LinalgError::DivisionByZero);
+        }
+
+        Ok(self / other)
+    }
+
+    // exact value of a finite float, e.g. 0.1 is 3602879701896397/36028797018963968.
+    // panics for nan and infinities
+    pub fn from_float(value: f64) -> Self {
+        Self::try_from_float(value).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_from_float(value: f64) -> Result<Self, LinalgError> {
+        if !value.is_finite() {
+            return Err(LinalgError::InvalidParameter {
+                parameter: "value",
+                requirement: "finite",
+            });
+        }
+
+        let bits = value.to_bits();
+        let exponent = ((bits >> 52) & 0x7ff) as i64;
+        let mut mantissa = bits & ((1 << 52) - 1);
+
+        // subnormals have no implicit leading one
+        let exponent = if exponent == 0 {
+            -1074
+        } else {
+            mantissa |= 1 << 52;
+            exponent - 1075
+        };
+
+        let mut numerator = BigInt::from(mantissa);
+
+        if value < 0.0 {
+            numerator = -numerator;
+        }
+
+        if exponent >= 0 {
+            Ok(Self::reduced(numerator.shl(exponent as u64), BigInt::from(1i64)))
+        } else {
+            Ok(Self::reduced(numerator, BigInt::from(1i64).shl(-exponent as u64)))
+        }
+    }
+
+    // nearest float, up to rounding of the last bit
+    pub fn to_f64(&self) -> f64 {
+        if self.numerator.is_zero() {
+            return 0.0;
+        }
+
+        // scale so that the integer quotient has about 64 bits
+        let shift = 64 + self.denominator.bits() as i64 - self.numerator.bits() as i64;
+
+        let quotient = if shift >= 0 {
+            self.numerator.shl(shift as u64).div_rem(&self.denominator).0
+        } else {
+            self.numerator.div_rem(&self.denominator.shl(-shift as u64)).0
+        };
+
+        // 2^-shift in two steps, so that neither factor overflows
+        let half = (-shift / 2) as i32;
+        quotient.to_f64() * 2f64.powi(half) * 2f64.powi(-shift as i32 - half)
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.numerator * &other.denominator).cmp(&(&other.numerator * &self.denominator))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Rational {
+    // always exact, a precision like {:.5} is ignored
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+impl Add<&Rational> for &Rational {
+    type Output = Rational;
+
+    fn add(self, other: &Rational) -> Self::Output {
+        let numerator = &(&self.numerator * &other.denominator)
+                        + &(&other.numerator * &self.denominator);
+
+        Rational::reduced(numerator, &self.denominator * &other.denominator)
+    }
+}
+
+impl Sub<&Rational> for &Rational {
+    type Output = Rational;
+
+    fn sub(self, other: &Rational) -> Self::Output {
+        let numerator = &(&self.numerator * &other.denominator)
+                        - &(&other.numerator * &self.denominator);
+
+        Rational::reduced(numerator, &self.denominator * &other.denominator)
+    }
+}
+
+impl Mul<&Rational> for &Rational {
+    type Output = Rational;
+
+    fn mul(self, other: &Rational) -> Self::Output {
+        Rational::reduced(&self.numerator * &other.numerator,
+                          &self.denominator * &other.denominator)
+    }
+}
+
+// panics on a zero divisor, try_div returns an error instead
+impl Div<&Rational> for &Rational {
+    type Output = Rational;
+
+    fn div(self, other: &Rational) -> Self::Output {
+        assert!(!other.numerator.is_zero(), "{}", LinalgError::DivisionByZero);
+
+        Rational::reduced(&self.numerator * &other.denominator,
+                          &self.denominator * &other.numerator)
+    }
+}
+
+impl Neg for &Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Self::Output {
+        Rational {
+            numerator: -&self.numerator,
+            denominator: self.denominator.clone(),
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, other: Rational) -> Self::Output {
+        Add::add(&self, &other)
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, other: Rational) -> Self::Output {
+        Sub::sub(&self, &other)
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, other: Rational) -> Self::Output {
+        Mul::mul(&self, &other)
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    fn div(self, other: Rational) -> Self::Output {
+        Div::div(&self, &other)
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Self::Output {
+        Rational {
+            numerator: -self.numerator,
+            denominator: self.denominator,
+        }
+    }
+}
+
+impl Scalar for Rational {
+    fn zero() -> Self {
+        Rational::from_integer(0)
+    }
+
+    fn one() -> Self {
+        Rational::from_integer(1)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        // panics for nan and infinities, which have no rational value
+        Rational::from_float(value)
+    }
+
+    fn modulus(&self) -> f64 {
+        // a nonzero value that underflows must still count as nonzero
+        // when pivots are compared with a zero tolerance
+        match self.abs().to_f64() {
+            m if m == 0.0 && !self.numerator.is_zero() => f64::MIN_POSITIVE,
+            m => m,
+        }
+    }
+
+    fn conjugate(&self) -> Self {
+        self.clone()
+    }
+
+    fn epsilon() -> f64 {
+        0.0
+    }
+
+    fn is_zero(&self) -> bool {
+        self.numerator.is_zero()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reduced_with_positive_denominator() {
+        let x = Rational::new(6, -4);
+
+        assert_eq!(x.numerator(), &BigInt::from(-3i64));
+        assert_eq!(x.denominator(), &BigInt::from(2i64));
+        assert_eq!(Rational::new(0, -5), Rational::from_integer(0));
+        assert_eq!(Rational::new(-1, -3).to_string(), "1/3");
+        assert_eq!(Rational::new(1, -3).to_string(), "-1/3");
+
+        let common = BigInt::from(3i64).shl(70);
+        let x = Rational::from_bigints(&common * &BigInt::from(5i64), &common * &BigInt::from(-7i64));
+
+        assert_eq!(x, Rational::new(-5, 7));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let (a, b) = (Rational::new(1, 6), Rational::new(-3, 4));
+
+        assert_eq!(&a + &b, Rational::new(-7, 12));
+        assert_eq!(&a - &b, Rational::new(11, 12));
+        assert_eq!(&a * &b, Rational::new(-1, 8));
+        assert_eq!(&a / &b, Rational::new(-2, 9));
+        assert!(b < a);
+    }
+
+    #[test]
+    fn division_by_zero() {
+        let zero = Rational::from_integer(0);
+
+        assert_eq!(Rational::try_new(1, 0), Err(LinalgError::DivisionByZero));
+        assert_eq!(Rational::new(1, 2).try_div(&zero), Err(LinalgError::DivisionByZero));
+        assert_eq!(zero.try_recip(), Err(LinalgError::DivisionByZero));
+    }
+
+    #[test]
+    fn float_round_trips() {
+        let values = [0.0, 1.0, -2.5, 0.1, 1.0 / 3.0, -1e300, 1e-300, 5e-324,
+                      f64::MIN_POSITIVE, f64::MAX, 123456789.0123];
+
+        for value in values {
+            assert_eq!(Rational::from_float(value).to_f64(), value);
+        }
+
+        assert_eq!(Rational::from_float(0.1).to_string(),
+                   "3602879701896397/36028797018963968");
+        assert_eq!(Rational::from_float(-0.75), Rational::new(-3, 4));
+        assert!((Rational::new(1, 3).to_f64() - 1.0 / 3.0).abs() <= f64::EPSILON / 3.0);
+    }
+
+    #[test]
+    fn non_finite_floats() {
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(matches!(Rational::try_from_float(value),
+                             Err(LinalgError::InvalidParameter { .. })));
+        }
+    }
+}
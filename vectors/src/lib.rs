@@ -1,99 +1,188 @@
 #![allow(dead_code, unused_imports, unused_variables, unused_mut)]
 
-use std::ops::{Add, Sub, Mul};
+use std::ops::{Add, Sub, Mul, Div, Neg};
 use std::f64::consts::PI;
 use std::fmt;
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct Vector {
-    pub components: Vec<f64>,
+pub use errors::LinalgError;
+
+// element type of vectors and matrices. Send + Sync so that the
+// matrix kernels can split the work between threads
+pub trait Scalar:
+    Clone + Send + Sync + PartialEq + fmt::Debug + fmt::Display
+    + Add<Output = Self> + Sub<Output = Self>
+    + Mul<Output = Self> + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    fn zero() -> Self;
+
+    fn one() -> Self;
+
+    fn from_f64(value: f64) -> Self;
+
+    // absolute value, used to choose pivots and to compare with tolerances
+    fn modulus(&self) -> f64;
+
+    fn conjugate(&self) -> Self;
+
+    // relative rounding error of the arithmetic, 0 for exact types
+    fn epsilon() -> f64;
+
+    fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
 }
 
-impl Vector {
-    pub fn new(numbers: &[f64]) -> Self {
-        assert!(numbers.len() > 0, "tried to create a vector with no components");
+impl Scalar for f64 {
+    fn zero() -> Self {
+        0.0
+    }
 
-        let mut components: Vec<f64> = vec![];
+    fn one() -> Self {
+        1.0
+    }
 
-        for &number in numbers.iter() {
-            components.push(number);
-        }
+    fn from_f64(value: f64) -> Self {
+        value
+    }
 
-        Self {
-            components,
+    fn modulus(&self) -> f64 {
+        self.abs()
+    }
+
+    fn conjugate(&self) -> Self {
+        *self
+    }
+
+    fn epsilon() -> f64 {
+        f64::EPSILON
+    }
+}
+
+impl Scalar for f32 {
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn one() -> Self {
+        1.0
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn modulus(&self) -> f64 {
+        self.abs() as f64
+    }
+
+    fn conjugate(&self) -> Self {
+        *self
+    }
+
+    fn epsilon() -> f64 {
+        f32::EPSILON as f64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vector<T = f64> {
+    pub components: Vec<T>,
+}
+
+impl<T: Scalar> Vector<T> {
+    pub fn new(numbers: &[T]) -> Self {
+        Self::try_new(numbers).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_new(numbers: &[T]) -> Result<Self, LinalgError> {
+        if numbers.is_empty() {
+            return Err(LinalgError::Empty);
         }
+
+        Ok(Self {
+            components: numbers.to_vec(),
+        })
     }
 
-    pub fn create_with_vec(numbers: Vec<f64>) -> Self {
+    pub fn create_with_vec(numbers: Vec<T>) -> Self {
         Self {
             components: numbers,
         }
     }
 
-    pub fn zero(size: usize) -> Vector {
-        let result = (0..size)
-                    .map(|i| 0.0)
-                    .collect::<Vec<f64>>();
-
-        Vector::new(&result)
+    pub fn zero(size: usize) -> Self {
+        Vector::new(&vec![T::zero(); size])
     }
 
     pub fn is_zero(&self) -> bool {
         self.components
             .iter()
-            .all(|&i| i == 0.0)
+            .all(|i| i.is_zero())
     }
 
     pub fn dimension(&self) -> (usize, usize) {
         (1, self.components.len())
     }
 
-    pub fn element(&self, position: usize) -> Option<f64> {
-        assert!(position > 0, "position must be greater than zero");
-        assert!(position <= self.size(), "position must be less or equal than
-            vector size");
+    pub fn element(&self, position: usize) -> Option<T> {
+        Some(self.try_element(position).unwrap_or_else(|error| panic!("{}", error)))
+    }
 
-        match self.components.get(position - 1) {
-            Some(&element) => Some(element),
-            None => None,
-        }
+    pub fn try_element(&self, position: usize) -> Result<T, LinalgError> {
+        self.check_position(position)?;
+
+        Ok(self.components[position - 1].clone())
     }
 
-    pub fn sign(&self, position: usize) -> f64 {
-        if let Some(element) = self.element(position) {
-            if element >= 0.0 {
-                return 1.0;
-            } else {
-                return -1.0;
-            }
-        } else {
-            panic!("bla blu tomou no cu");
+    fn check_position(&self, position: usize) -> Result<(), LinalgError> {
+        if position == 0 || position > self.size() {
+            return Err(LinalgError::IndexOutOfBounds {
+                index: (1, position),
+                dimension: self.dimension(),
+            });
         }
+
+        Ok(())
+    }
+
+    pub fn canonical(index: usize, size: usize) -> Self {
+        Self::try_canonical(index, size).unwrap_or_else(|error| panic!("{}", error))
     }
 
-    pub fn canonical(index: usize, size: usize) -> Vector {
-        assert!(size > 0);
-        assert!(index > 0);
-        assert!(index <= size);
+    pub fn try_canonical(index: usize, size: usize) -> Result<Self, LinalgError> {
+        if size == 0 {
+            return Err(LinalgError::Empty);
+        }
 
         let mut e = Vector::zero(size);
-        e.change_element(index, 1.0);
+        e.try_change_element(index, T::one())?;
 
-        e
+        Ok(e)
     }
 
     pub fn change_element
     (
-        &mut self, 
-        position: usize, 
-        element: f64
+        &mut self,
+        position: usize,
+        element: T
     )
     {
-        assert!(position > 0, "position must be greater than zero"); 
-        assert!(position <= self.size(), "position must be less or equal than
-            vector size");
+        self.try_change_element(position, element)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
 
+    pub fn try_change_element
+    (
+        &mut self,
+        position: usize,
+        element: T
+    ) -> Result<(), LinalgError>
+    {
+        self.check_position(position)?;
         self.components[position - 1] = element;
+
+        Ok(())
     }
 
     pub fn size(&self) -> usize {
@@ -101,53 +190,82 @@ impl Vector {
     }
 
     pub fn inverse(&self) -> Self {
-        self * (-1.0)
+        Vector::create_with_vec(self.components.iter().map(|x| -x.clone()).collect())
     }
 
-    fn check_sizes(&self, other: &Self) -> bool {
-        self.components.len() == other.components.len()
+    fn check_sizes(&self, other: &Self) -> Result<(), LinalgError> {
+        if self.components.len() != other.components.len() {
+            return Err(LinalgError::DimensionMismatch {
+                expected: self.dimension(),
+                found: other.dimension(),
+            });
+        }
+
+        Ok(())
     }
 
-    pub fn dot_product(&self, other: &Self) -> f64 {
-        let length = self.components.len();
+    pub fn dot_product(&self, other: &Self) -> T {
+        self.try_dot_product(other).unwrap_or_else(|error| panic!("{}", error))
+    }
 
-        assert!(self.check_sizes(other),
-        "tried to calculate the dot product of vectors of different sizes");
+    pub fn try_dot_product(&self, other: &Self) -> Result<T, LinalgError> {
+        // conjugates self, so for complex vectors x.dot_product(&x) = |x|^2
+        self.check_sizes(other)?;
 
-        self.components.iter()
+        Ok(self.components.iter()
             .zip(other.components.iter())
-            .map(|(num1, num2)| num1 * num2)
-            .sum::<f64>()
+            .fold(T::zero(), |sum, (num1, num2)| sum + num1.conjugate() * num2.clone()))
     }
 
     pub fn magnitude(&self) -> f64 {
-        self.dot_product(self).sqrt()
+        self.components.iter()
+            .map(|x| x.modulus() * x.modulus())
+            .sum::<f64>()
+            .sqrt()
     }
 
     pub fn normalize(&self) -> Self {
-        let magnitude = self.magnitude();
+        let magnitude = T::from_f64(self.magnitude());
         let mut components = self.components
                         .iter()
-                        .map(|num| num / magnitude)
+                        .map(|num| num.clone() / magnitude.clone())
                         .collect::<Vec<_>>();
 
         Self::new(&components)
     }
 
-    pub fn angle(&self, other: &Self) -> f64 {
-        assert!(self.check_sizes(other),
-        "tried to calculate the angle between vectors of different sizes");
+    pub fn try_normalize(&self) -> Result<Self, LinalgError> {
+        if self.is_zero() {
+            return Err(LinalgError::DivisionByZero);
+        }
 
-        (self.dot_product(other) / 
-        (self.magnitude() * other.magnitude())).acos()
+        Ok(self.normalize())
     }
 
-    pub fn angle_degrees(&self, other: &Self) -> f64 {
-        self.angle(other) * 180.0 / PI
+    pub fn proj(&self, other: &Self) -> Self {
+        other * (other.dot_product(self) / other.dot_product(other))
     }
 
-    pub fn proj(&self, other: &Self) -> Self {
-        other * ( (self.dot_product(other)) / (other.dot_product(other)))
+    pub fn try_add(&self, other: &Self) -> Result<Self, LinalgError> {
+        self.check_sizes(other)?;
+
+        let result = self.components.iter()
+                     .zip(other.components.iter())
+                     .map(|(a, b)| a.clone() + b.clone())
+                     .collect::<Vec<T>>();
+
+        Ok(Vector::create_with_vec(result))
+    }
+
+    pub fn try_sub(&self, other: &Self) -> Result<Self, LinalgError> {
+        self.check_sizes(other)?;
+
+        let result = self.components.iter()
+                     .zip(other.components.iter())
+                     .map(|(a, b)| a.clone() - b.clone())
+                     .collect::<Vec<T>>();
+
+        Ok(Vector::create_with_vec(result))
     }
 
     pub fn max_index(&self, init: usize, fin: usize) -> usize {
@@ -157,8 +275,8 @@ impl Vector {
         let mut index = 0;
 
         for i in init..fin {
-            if self.components[i].abs() > max {
-                max = self.components[i].abs();
+            if self.components[i].modulus() > max {
+                max = self.components[i].modulus();
                 index = i;
             }
         }
@@ -167,7 +285,37 @@ impl Vector {
     }
 }
 
-impl fmt::Display for Vector {
+impl Vector {
+    pub fn sign(&self, position: usize) -> f64 {
+        let element = self.element(position).unwrap();
+
+        if element >= 0.0 {
+            1.0
+        } else {
+            -1.0
+        }
+    }
+
+    pub fn angle(&self, other: &Self) -> f64 {
+        self.try_angle(other).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_angle(&self, other: &Self) -> Result<f64, LinalgError> {
+        let dot = self.try_dot_product(other)?;
+
+        if self.is_zero() || other.is_zero() {
+            return Err(LinalgError::DivisionByZero);
+        }
+
+        Ok((dot / (self.magnitude() * other.magnitude())).acos())
+    }
+
+    pub fn angle_degrees(&self, other: &Self) -> f64 {
+        self.angle(other) * 180.0 / PI
+    }
+}
+
+impl<T: Scalar> fmt::Display for Vector<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "[")?;
         for (i, value) in self.components.iter().enumerate() {
@@ -180,56 +328,29 @@ impl fmt::Display for Vector {
     }
 }
 
-impl Add<&Vector> for &Vector {
-    type Output = Vector;
-
-    fn add(self, other: &Vector) -> Vector {
-        let length = self.components.len();
+impl<T: Scalar> Add<&Vector<T>> for &Vector<T> {
+    type Output = Vector<T>;
 
-        if length != other.components.len() {
-            panic!("tried to add vectors of different sizes");
-        }
-
-        let mut result = vec![];
-
-        for i in 0..length {
-            result.push(self.components[i] + other.components[i]);
-        }
-
-        Vector::new(&result)
+    fn add(self, other: &Vector<T>) -> Vector<T> {
+        self.try_add(other).unwrap_or_else(|error| panic!("{}", error))
     }
 }
 
-impl Sub<&Vector> for &Vector {
-    type Output = Vector;
-
-    fn sub(self, other: &Vector) -> Vector {
-        let length = self.components.len();
-
-        if length != other.components.len() {
-            panic!("tried to sub vectors of different sizes");
-        }
-
-        let mut result = vec![];
+impl<T: Scalar> Sub<&Vector<T>> for &Vector<T> {
+    type Output = Vector<T>;
 
-        for i in 0..length {
-            result.push(self.components[i] - other.components[i]);
-        }
-
-        Vector::new(&result)
+    fn sub(self, other: &Vector<T>) -> Vector<T> {
+        self.try_sub(other).unwrap_or_else(|error| panic!("{}", error))
     }
 }
 
-impl Mul<f64> for &Vector {
-    type Output = Vector;
+impl<T: Scalar> Mul<T> for &Vector<T> {
+    type Output = Vector<T>;
 
-    fn mul(self, other: f64) -> Vector {
-        let length = self.components.len();
-        let mut result = vec![];
-
-        for i in 0..length {
-            result.push(self.components[i] * other);
-        }
+    fn mul(self, other: T) -> Vector<T> {
+        let result = self.components.iter()
+                     .map(|x| x.clone() * other.clone())
+                     .collect::<Vec<T>>();
 
         Vector::new(&result)
     }
@@ -239,13 +360,14 @@ impl Mul<&Vector> for f64 {
     type Output = Vector;
 
     fn mul(self, other: &Vector) -> Vector {
-        let length = other.components.len();
-        let mut result = vec![];
+        other * self
+    }
+}
 
-        for i in 0..length {
-            result.push(self * other.components[i]);
-        }
+impl Mul<&Vector<f32>> for f32 {
+    type Output = Vector<f32>;
 
-        Vector::new(&result)
+    fn mul(self, other: &Vector<f32>) -> Vector<f32> {
+        other * self
     }
-}
\ No newline at end of file
+}